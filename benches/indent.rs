@@ -0,0 +1,74 @@
+// Formats a file nested deep enough that most of every output line is
+// indentation, and compares the two ways of writing that indentation: one
+// char at a time from an iterator, or in slices of a static buffer of spaces
+// as the printer does.
+//
+//     cargo bench --bench indent
+
+use std::hint::black_box;
+use std::iter;
+use std::time::{Duration, Instant};
+
+const SPACES: &str = "                                                                ";
+
+fn main() {
+    let src = deeply_indented_file(24, 40);
+    let file = syn::parse_file(&src).unwrap();
+
+    let formatted = prettyplease::unparse(&file);
+    let unparse = time(20, || {
+        black_box(prettyplease::unparse(black_box(&file)));
+    });
+    println!("unparse:      {:>10.3?} per file", unparse);
+
+    let indents: Vec<usize> = formatted
+        .lines()
+        .map(|line| line.len() - line.trim_start().len())
+        .collect();
+    let mut out = String::with_capacity(formatted.len());
+    let repeat = time(200, || {
+        out.clear();
+        for &n in black_box(&indents) {
+            out.extend(iter::repeat(' ').take(n));
+        }
+        black_box(&out);
+    });
+    let spaces = time(200, || {
+        out.clear();
+        for &n in black_box(&indents) {
+            let mut remaining = n;
+            while remaining > 0 {
+                let n = remaining.min(SPACES.len());
+                out.push_str(&SPACES[..n]);
+                remaining -= n;
+            }
+        }
+        black_box(&out);
+    });
+    println!("iter::repeat: {:>10.3?} per file", repeat);
+    println!("SPACES:       {:>10.3?} per file", spaces);
+}
+
+// Modules nested `depth` deep, each with a function of `stmts` statements
+// whose bodies nest a few blocks further.
+fn deeply_indented_file(depth: usize, stmts: usize) -> String {
+    let mut src = String::new();
+    for i in 0..depth {
+        src += &format!("mod m{} {{ fn f() {{", i);
+        for j in 0..stmts {
+            src += &format!("if x{} {{ loop {{ let y = z.method({}); }} }}", j, j);
+        }
+        src += "}";
+    }
+    src += &"}".repeat(depth);
+    src
+}
+
+fn time(iterations: u32, mut f: impl FnMut()) -> Duration {
+    f();
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    start.elapsed() / iterations
+}
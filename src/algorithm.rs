@@ -1,12 +1,15 @@
 // Adapted from https://github.com/rust-lang/rust/blob/1.57.0/compiler/rustc_ast_pretty/src/pp.rs.
 // See "Algorithm notes" in the crate-level rustdoc.
 
+use crate::config::{Config, EmptyBlock, WidthMetric};
+use crate::error::Error;
+use crate::report::TransformationEvent;
 use crate::ring::RingBuffer;
-use crate::{MARGIN, MIN_SPACE};
+use crate::sink::Sink;
+use crate::{MARGIN, MAX_DEPTH, MIN_SPACE};
 use std::borrow::Cow;
 use std::cmp;
 use std::collections::VecDeque;
-use std::iter;
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum Breaks {
@@ -47,8 +50,11 @@ enum PrintFrame {
 
 pub const SIZE_INFINITY: isize = 0xffff;
 
-pub struct Printer {
-    out: String,
+// Enough for 16 levels of indentation in a single push_str.
+const SPACES: &str = "                                                                ";
+
+pub struct Printer<'a> {
+    out: &'a mut dyn Sink,
     // Number of spaces left on line
     space: isize,
     // Ring-buffer of tokens and calculated sizes
@@ -69,6 +75,19 @@ pub struct Printer {
     indent: usize,
     // Buffered indentation to avoid writing trailing whitespace
     pending_indentation: usize,
+    // Number of expressions, patterns and types currently being printed, one
+    // inside the next
+    depth: usize,
+    // Whether some part of the syntax tree was skipped for being nested more
+    // than MAX_DEPTH levels deep
+    too_deep: bool,
+    // Whether the outer attributes at the start of the expression statement
+    // being printed were already printed on lines of their own
+    leading_attrs_printed: bool,
+    // Changes made to the code, if the config asks for them to be recorded
+    events: Vec<TransformationEvent>,
+    // User-selected formatting options
+    pub config: Config,
 }
 
 #[derive(Clone)]
@@ -77,10 +96,10 @@ struct BufEntry {
     size: isize,
 }
 
-impl Printer {
-    pub fn new() -> Self {
+impl<'a> Printer<'a> {
+    pub fn new(config: Config, out: &'a mut dyn Sink) -> Self {
         Printer {
-            out: String::new(),
+            out,
             space: MARGIN,
             buf: RingBuffer::new(),
             left_total: 0,
@@ -89,15 +108,19 @@ impl Printer {
             print_stack: Vec::new(),
             indent: 0,
             pending_indentation: 0,
+            depth: 0,
+            too_deep: false,
+            leading_attrs_printed: false,
+            events: Vec::new(),
+            config,
         }
     }
 
-    pub fn eof(mut self) -> String {
+    pub fn eof(mut self) {
         if !self.scan_stack.is_empty() {
             self.check_stack(0);
             self.advance_left();
         }
-        self.out
     }
 
     pub fn scan_begin(&mut self, token: BeginToken) {
@@ -126,6 +149,9 @@ impl Printer {
                             self.scan_stack.pop_back();
                             self.scan_stack.pop_back();
                             self.right_total -= break_token.blank_space as isize;
+                            if break_token.if_nonempty {
+                                self.empty_block();
+                            }
                             return;
                         }
                     }
@@ -133,6 +159,7 @@ impl Printer {
                         self.buf.pop_last();
                         self.scan_stack.pop_back();
                         self.right_total -= break_token.blank_space as isize;
+                        self.empty_block();
                     }
                 }
             }
@@ -144,6 +171,26 @@ impl Printer {
         }
     }
 
+    // The contents of a brace-delimited body turned out to be empty, or are
+    // known to be empty up front.
+    pub fn empty_block(&mut self) {
+        if self.config.empty_block_style != EmptyBlock::Spaced {
+            return;
+        }
+        if self.scan_stack.is_empty() && !self.buf.is_empty() {
+            // The break that was just removed was the last thing holding back
+            // the rest of the buffer, which is now all measured but not yet
+            // printed. Queue the space behind it instead of printing it ahead.
+            self.buf.push(BufEntry {
+                token: Token::String(Cow::Borrowed(" ")),
+                size: 1,
+            });
+            self.right_total += 1;
+        } else {
+            self.scan_string(Cow::Borrowed(" "));
+        }
+    }
+
     pub fn scan_break(&mut self, token: BreakToken) {
         if self.scan_stack.is_empty() {
             self.left_total = 1;
@@ -164,7 +211,7 @@ impl Printer {
         if self.scan_stack.is_empty() {
             self.print_string(string);
         } else {
-            let len = string.len() as isize;
+            let len = self.width(&string);
             self.buf.push(BufEntry {
                 token: Token::String(string),
                 size: len,
@@ -211,6 +258,90 @@ impl Printer {
         self.scan_end();
     }
 
+    // Treat the innermost open box, and so every box enclosing it, as too wide
+    // to fit on the current line regardless of its actual contents.
+    pub fn force_break(&mut self) {
+        if self.scan_stack.is_empty() {
+            return;
+        }
+        self.buf.push(BufEntry {
+            token: Token::String(Cow::Borrowed("")),
+            size: SIZE_INFINITY,
+        });
+        self.right_total += SIZE_INFINITY;
+        self.check_stream();
+    }
+
+    // Indentation of the line started by the hard break that was just
+    // scanned. A hard break never fits, so it and everything before it can be
+    // printed right away, which is what decides the indentation.
+    pub fn indent_after_hardbreak(&mut self) -> usize {
+        self.check_stream();
+        self.pending_indentation
+    }
+
+    // Lay out the rest as if starting at column `indent` of a line.
+    pub fn start_at(&mut self, indent: usize) {
+        self.space = cmp::max(MARGIN - indent as isize, MIN_SPACE);
+    }
+
+    pub fn record(&mut self, option: &'static str, description: impl FnOnce() -> String) {
+        if self.config.record_transformations {
+            let description = description();
+            self.events.push(TransformationEvent {
+                option,
+                description,
+            });
+        }
+    }
+
+    pub fn take_events(&mut self) -> Vec<TransformationEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // Printing a syntax tree recurses once per level of nesting. Instead of
+    // overflowing the stack on input that is nested too deeply for that to be
+    // safe, print a compile_error! in place of the part that is too deep and
+    // remember to report an error. The macro call is valid as an expression,
+    // pattern or type. Returns whether to go ahead and print the nested node.
+    pub fn enter_nested(&mut self) -> bool {
+        if self.depth == MAX_DEPTH {
+            self.too_deep = true;
+            self.word(format!(
+                "compile_error!({:?})",
+                Error::too_deep().to_string()
+            ));
+            return false;
+        }
+        self.depth += 1;
+        true
+    }
+
+    // Count nesting from where `parent` is, for a printer that lays out part of
+    // what `parent` is printing.
+    pub fn nest_within(&mut self, parent: &Printer) {
+        self.depth = parent.depth;
+    }
+
+    pub fn leave_nested(&mut self) {
+        self.depth -= 1;
+    }
+
+    pub fn is_too_deep(&self) -> bool {
+        self.too_deep
+    }
+
+    // The attributes at the start of an expression statement are printed
+    // before the statement's boxes open, and then skipped by the expression
+    // that they belong to.
+    pub fn set_leading_attrs_printed(&mut self, printed: bool) {
+        self.leading_attrs_printed = printed;
+    }
+
+    pub fn take_leading_attrs_printed(&mut self) -> bool {
+        std::mem::replace(&mut self.leading_attrs_printed, false)
+    }
+
     fn check_stream(&mut self) {
         while self.right_total - self.left_total > self.space {
             if *self.scan_stack.front().unwrap() == self.buf.index_of_first() {
@@ -278,31 +409,36 @@ impl Printer {
         }
     }
 
+    fn debug_markers(&self) -> bool {
+        cfg!(prettyplease_debug) || self.config.debug_markers
+    }
+
     fn get_top(&self) -> PrintFrame {
         const OUTER: PrintFrame = PrintFrame::Broken(0, Breaks::Inconsistent);
         self.print_stack.last().map_or(OUTER, PrintFrame::clone)
     }
 
     fn print_begin(&mut self, token: BeginToken, size: isize) {
-        if cfg!(prettyplease_debug) {
-            self.out.push(match token.breaks {
+        if self.debug_markers() {
+            self.out.push_char(match token.breaks {
                 Breaks::Consistent => '«',
                 Breaks::Inconsistent => '‹',
             });
             if cfg!(prettyplease_debug_indent) {
-                self.out
-                    .extend(token.offset.to_string().chars().map(|ch| match ch {
+                for ch in token.offset.to_string().chars() {
+                    self.out.push_char(match ch {
                         '0'..='9' => ['₀', '₁', '₂', '₃', '₄', '₅', '₆', '₇', '₈', '₉']
                             [(ch as u8 - b'0') as usize],
                         '-' => '₋',
                         _ => unreachable!(),
-                    }));
+                    });
+                }
             }
         }
         if size > self.space {
             self.print_stack
                 .push(PrintFrame::Broken(self.indent, token.breaks));
-            self.indent = usize::try_from(self.indent as isize + token.offset).unwrap();
+            self.indent = cmp::max(self.indent as isize + token.offset, 0) as usize;
         } else {
             self.print_stack.push(PrintFrame::Fits(token.breaks));
         }
@@ -316,8 +452,8 @@ impl Printer {
             }
             PrintFrame::Fits(breaks) => breaks,
         };
-        if cfg!(prettyplease_debug) {
-            self.out.push(match breaks {
+        if self.debug_markers() {
+            self.out.push_char(match breaks {
                 Breaks::Consistent => '»',
                 Breaks::Inconsistent => '›',
             });
@@ -335,27 +471,29 @@ impl Printer {
             self.pending_indentation += token.blank_space;
             self.space -= token.blank_space as isize;
             if let Some(no_break) = token.no_break {
-                self.out.push(no_break);
+                self.out.push_char(no_break);
                 self.space -= no_break.len_utf8() as isize;
             }
-            if cfg!(prettyplease_debug) {
-                self.out.push('·');
+            if self.debug_markers() {
+                self.out.push_char('·');
             }
         } else {
             if let Some(pre_break) = token.pre_break {
                 self.print_indent();
-                self.out.push(pre_break);
+                self.out.push_char(pre_break);
             }
-            if cfg!(prettyplease_debug) {
-                self.out.push('·');
+            if self.debug_markers() {
+                self.out.push_char('·');
             }
-            self.out.push('\n');
-            let indent = self.indent as isize + token.offset;
-            self.pending_indentation = usize::try_from(indent).unwrap();
+            self.out.push_char('\n');
+            // A negative offset can never dedent past the left margin, no
+            // matter how unbalanced the surrounding boxes' offsets are.
+            let indent = cmp::max(self.indent as isize + token.offset, 0);
+            self.pending_indentation = indent as usize;
             self.space = cmp::max(MARGIN - indent, MIN_SPACE);
             if let Some(post_break) = token.post_break {
                 self.print_indent();
-                self.out.push(post_break);
+                self.out.push_char(post_break);
                 self.space -= post_break.len_utf8() as isize;
             }
         }
@@ -363,14 +501,68 @@ impl Printer {
 
     fn print_string(&mut self, string: Cow<'static, str>) {
         self.print_indent();
-        self.out.push_str(&string);
-        self.space -= string.len() as isize;
+        // Hold back trailing spaces the same way as indentation, so that they
+        // are dropped if a line break comes next. String literal tokens end in
+        // a quote so their contents are never affected.
+        let trimmed = string.trim_end_matches(' ');
+        self.out.push_str(trimmed);
+        self.pending_indentation += string.len() - trimmed.len();
+        self.space -= self.width(&string);
+    }
+
+    pub fn width(&self, string: &str) -> isize {
+        width(self.config.width_metric, string) as isize
     }
 
     fn print_indent(&mut self) {
-        self.out.reserve(self.pending_indentation);
-        self.out
-            .extend(iter::repeat(' ').take(self.pending_indentation));
+        let mut remaining = self.pending_indentation;
+        while remaining > 0 {
+            let n = cmp::min(remaining, SPACES.len());
+            self.out.push_str(&SPACES[..n]);
+            remaining -= n;
+        }
         self.pending_indentation = 0;
     }
 }
+
+pub fn width(metric: WidthMetric, string: &str) -> usize {
+    match metric {
+        WidthMetric::Bytes => string.len(),
+        WidthMetric::Chars => string.chars().count(),
+        WidthMetric::UnicodeWidth => string.chars().map(char_width).sum(),
+    }
+}
+
+// Terminal columns taken up by `ch`, for WidthMetric::UnicodeWidth. The zero
+// width ranges are the combining diacritical mark blocks, zero width spaces
+// and joiners, and variation selectors. The double width ranges are the
+// blocks of the East Asian Width property that are wholly wide or fullwidth,
+// plus the two emoji blocks that are. Anything else counts as 1, which is
+// right for the rest of the alphabetic scripts and wrong for some symbols.
+fn char_width(ch: char) -> usize {
+    match ch as u32 {
+        0x0300..=0x036F
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200B..=0x200F
+        | 0x20D0..=0x20FF
+        | 0xFE00..=0xFE0F
+        | 0xFE20..=0xFE2F => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x2FFFD
+        | 0x30000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
@@ -1,11 +1,20 @@
 use crate::algorithm::Printer;
+use crate::config::{Config, DocStyle};
 use crate::path::PathKind;
 use crate::INDENT;
-use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+use proc_macro2::{Delimiter, Group, Punct, Spacing, TokenStream, TokenTree};
+use std::ptr;
 use syn::{AttrStyle, Attribute, Expr, Lit, MacroDelimiter, Meta, MetaList, MetaNameValue};
 
-impl Printer {
+impl Printer<'_> {
     pub fn outer_attrs(&mut self, attrs: &[Attribute]) {
+        if has_outer(attrs) && self.take_leading_attrs_printed() {
+            return;
+        }
+        if self.config.sort_attributes {
+            self.sorted_outer_attrs(attrs);
+            return;
+        }
         for attr in attrs {
             if let AttrStyle::Outer = attr.style {
                 self.attr(attr);
@@ -13,6 +22,42 @@ impl Printer {
         }
     }
 
+    fn sorted_outer_attrs(&mut self, attrs: &[Attribute]) {
+        let mut docs = Vec::new();
+        let mut others = Vec::new();
+        for attr in attrs {
+            if let AttrStyle::Outer = attr.style {
+                if attr.path().is_ident("doc") {
+                    docs.push(attr);
+                } else {
+                    others.push((attr_sort_key(self, attr), attr));
+                }
+            }
+        }
+        others.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let sorted = docs
+            .iter()
+            .copied()
+            .chain(others.iter().map(|(_key, attr)| *attr));
+        let original = attrs
+            .iter()
+            .filter(|attr| matches!(attr.style, AttrStyle::Outer));
+        if !sorted.zip(original).all(|(a, b)| ptr::eq(a, b)) {
+            self.record("sort_attributes", || {
+                let paths: Vec<&str> = others.iter().map(|((path, _), _)| path.as_str()).collect();
+                format!("sorted attributes into the order {}", paths.join(", "))
+            });
+        }
+
+        for attr in docs {
+            self.attr(attr);
+        }
+        for (_key, attr) in others {
+            self.attr(attr);
+        }
+    }
+
     pub fn inner_attrs(&mut self, attrs: &[Attribute]) {
         for attr in attrs {
             if let AttrStyle::Inner(_) = attr.style {
@@ -23,6 +68,34 @@ impl Printer {
 
     fn attr(&mut self, attr: &Attribute) {
         if let Some(mut doc) = value_of_attribute("doc", attr) {
+            match self.config.doc_comment_style {
+                Some(DocStyle::LineComments) if can_be_line_comments(&doc, &attr.style) => {
+                    self.doc_line_comments(&doc, &attr.style);
+                    return;
+                }
+                Some(DocStyle::BlockComments)
+                    if doc.contains('\n') && can_be_block_comment(&doc) =>
+                {
+                    self.doc_block_comment(&doc, &attr.style);
+                    return;
+                }
+                Some(DocStyle::BlockComments)
+                    if !doc.is_empty()
+                        && !doc.starts_with(&['*', '/'][..])
+                        && can_be_block_comment(&doc) =>
+                {
+                    trim_trailing_spaces(&mut doc);
+                    self.word(match attr.style {
+                        AttrStyle::Outer => "/**",
+                        AttrStyle::Inner(_) => "/*!",
+                    });
+                    self.word(doc);
+                    self.word(" */");
+                    self.hardbreak();
+                    return;
+                }
+                _ => {}
+            }
             if !doc.contains('\n')
                 && match attr.style {
                     AttrStyle::Outer => !doc.starts_with('/'),
@@ -77,9 +150,48 @@ impl Printer {
         self.word("[");
         self.meta(&attr.meta);
         self.word("]");
+        // Attributes on items, statements, fields and variants are all in a
+        // consistent box that ends with a hardbreak, so this always breaks
+        // there, giving each attribute its own line. It stays a space only on
+        // expressions and parameters that fit on one line.
         self.space();
     }
 
+    fn doc_line_comments(&mut self, doc: &str, style: &AttrStyle) {
+        for line in doc_lines(doc) {
+            self.word(match style {
+                AttrStyle::Outer => "///",
+                AttrStyle::Inner(_) => "//!",
+            });
+            self.word(line.trim_end_matches(' ').to_owned());
+            self.hardbreak();
+        }
+    }
+
+    // Multi-line block doc comments are written the conventional way, with the
+    // opening and closing delimiter on their own lines and a `*` in front of
+    // every line in between.
+    fn doc_block_comment(&mut self, doc: &str, style: &AttrStyle) {
+        self.word(match style {
+            AttrStyle::Outer => "/**",
+            AttrStyle::Inner(_) => "/*!",
+        });
+        self.hardbreak();
+        for line in doc_lines(doc) {
+            let line = line.trim_end_matches(' ');
+            if line.is_empty() {
+                self.word(" *");
+            } else if line.starts_with(' ') {
+                self.word(format!(" *{}", line));
+            } else {
+                self.word(format!(" * {}", line));
+            }
+            self.hardbreak();
+        }
+        self.word(" */");
+        self.hardbreak();
+    }
+
     fn meta(&mut self, meta: &Meta) {
         match meta {
             Meta::Path(path) => self.path(path, PathKind::Simple),
@@ -95,8 +207,20 @@ impl Printer {
             MacroDelimiter::Brace(_) => Delimiter::Brace,
             MacroDelimiter::Bracket(_) => Delimiter::Bracket,
         };
-        let group = Group::new(delimiter, meta.tokens.clone());
-        self.attr_tokens(TokenStream::from(TokenTree::Group(group)));
+        let mut tokens = meta.tokens.clone();
+        if self.config.sort_lint_attributes && is_lint_attribute(meta) {
+            if let Some(sorted) = sorted_lints(&tokens) {
+                if sorted.to_string() != tokens.to_string() {
+                    self.record("sort_lint_attributes", || {
+                        format!("sorted the lints in `{}(..)`", lint_level(meta))
+                    });
+                }
+                tokens = sorted;
+            }
+        }
+        let group = Group::new(delimiter, tokens);
+        let meta = true;
+        self.attr_tokens(TokenStream::from(TokenTree::Group(group)), meta);
     }
 
     fn meta_name_value(&mut self, meta: &MetaNameValue) {
@@ -105,10 +229,16 @@ impl Printer {
         self.expr(&meta.value);
     }
 
-    fn attr_tokens(&mut self, tokens: TokenStream) {
+    // Prints the arguments of an attribute, or other tokens that syn does not
+    // parse. With `meta`, `tokens` is the parenthesized argument list of an
+    // attribute like `#[path(...)]`.
+    pub fn attr_tokens(&mut self, tokens: TokenStream, meta: bool) {
         let mut stack = Vec::new();
-        stack.push((tokens.into_iter().peekable(), Delimiter::None));
+        stack.push((tokens.into_iter().peekable(), Delimiter::None, false, meta));
         let mut space = Self::nbsp as fn(&mut Self);
+        // Whether the current element of a nested meta list is so far only a
+        // path, which a parenthesized nested meta list may follow.
+        let mut path_only = meta;
 
         #[derive(PartialEq)]
         enum State {
@@ -118,9 +248,10 @@ impl Printer {
         }
 
         use State::*;
-        let mut state = Word;
+        let mut state = Punct;
 
-        while let Some((tokens, delimiter)) = stack.last_mut() {
+        while let Some((tokens, delimiter, is_list, is_meta)) = stack.last_mut() {
+            let is_meta = *is_meta;
             match tokens.next() {
                 Some(TokenTree::Ident(ident)) => {
                     if let Word = state {
@@ -131,6 +262,11 @@ impl Printer {
                 }
                 Some(TokenTree::Punct(punct)) => {
                     let ch = punct.as_char();
+                    path_only = match ch {
+                        ',' => true,
+                        ':' => path_only,
+                        _ => false,
+                    };
                     if let (Word, '=') = (state, ch) {
                         self.nbsp();
                     }
@@ -148,6 +284,7 @@ impl Printer {
                     }
                 }
                 Some(TokenTree::Literal(literal)) => {
+                    path_only = false;
                     if let Word = state {
                         space(self);
                     }
@@ -157,6 +294,21 @@ impl Printer {
                 Some(TokenTree::Group(group)) => {
                     let delimiter = group.delimiter();
                     let stream = group.stream();
+                    // A nested meta list, like the one in `derive(...)`,
+                    // `serde(...)` or `cfg(all(...))`, gets a trailing comma
+                    // when broken one element per line. Arbitrary tokens are
+                    // left alone, as a trailing comma may not be allowed in
+                    // them.
+                    let is_meta = is_meta
+                        && path_only
+                        && delimiter == Delimiter::Parenthesis
+                        && is_nested_meta(stream.clone());
+                    let is_list = is_meta
+                        && stream.clone().into_iter().any(|token| match token {
+                            TokenTree::Punct(punct) => punct.as_char() == ',',
+                            _ => false,
+                        });
+                    path_only = is_meta;
                     match delimiter {
                         Delimiter::Parenthesis => {
                             self.word("(");
@@ -174,14 +326,18 @@ impl Printer {
                         }
                         Delimiter::None => {}
                     }
-                    stack.push((stream.into_iter().peekable(), delimiter));
+                    stack.push((stream.into_iter().peekable(), delimiter, is_list, is_meta));
                     space = Self::space;
                 }
                 None => {
                     match delimiter {
                         Delimiter::Parenthesis => {
                             if state != TrailingComma {
-                                self.zerobreak();
+                                if *is_list {
+                                    self.trailing_comma(true);
+                                } else {
+                                    self.zerobreak();
+                                }
                             }
                             self.offset(-INDENT);
                             self.end();
@@ -199,6 +355,7 @@ impl Printer {
                         Delimiter::None => {}
                     }
                     stack.pop();
+                    path_only = false;
                     if stack.is_empty() {
                         space = Self::nbsp;
                     }
@@ -208,6 +365,44 @@ impl Printer {
     }
 }
 
+// Whether `tokens` is a comma-separated list of elements of the form `path`,
+// `path = value` or `path(...)`, the syntax that most attributes use for their
+// arguments.
+fn is_nested_meta(tokens: TokenStream) -> bool {
+    enum State {
+        Start,
+        Path,
+        Colon,
+        Colon2,
+        Value,
+        List,
+    }
+
+    use State::*;
+
+    let mut state = Start;
+    for token in tokens {
+        state = match (state, token) {
+            (Start | Colon2, TokenTree::Ident(_)) => Path,
+            (Start | Path, TokenTree::Punct(punct))
+                if punct.as_char() == ':' && punct.spacing() == Spacing::Joint =>
+            {
+                Colon
+            }
+            (Colon, TokenTree::Punct(punct)) if punct.as_char() == ':' => Colon2,
+            (Path, TokenTree::Punct(punct)) if punct.as_char() == '=' => Value,
+            (Path, TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => List,
+            (Path | Value | List, TokenTree::Punct(punct)) if punct.as_char() == ',' => Start,
+            (Value, _) => Value,
+            _ => return false,
+        };
+    }
+    match state {
+        Start | Path | Value | List => true,
+        Colon | Colon2 => false,
+    }
+}
+
 fn value_of_attribute(requested: &str, attr: &Attribute) -> Option<String> {
     let value = match &attr.meta {
         Meta::NameValue(meta) if meta.path.is_ident(requested) => &meta.value,
@@ -241,6 +436,35 @@ pub fn has_inner(attrs: &[Attribute]) -> bool {
     false
 }
 
+// Lines of a doc attribute without the blank first and last line and the
+// leading `*` of every line that a block doc comment typically has.
+fn doc_lines(doc: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = doc.split('\n').collect();
+    if lines.len() == 1 {
+        return lines;
+    }
+    if lines.first().map_or(false, |line| line.trim().is_empty()) {
+        lines.remove(0);
+    }
+    if lines.last().map_or(false, |line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    if lines.iter().all(|line| line.trim_start().starts_with('*')) {
+        for line in &mut lines {
+            *line = &line.trim_start()[1..];
+        }
+    }
+    lines
+}
+
+fn can_be_line_comments(doc: &str, style: &AttrStyle) -> bool {
+    match style {
+        // `////` is an ordinary comment, not a doc comment.
+        AttrStyle::Outer => !doc_lines(doc).iter().any(|line| line.starts_with('/')),
+        AttrStyle::Inner(_) => true,
+    }
+}
+
 fn trim_trailing_spaces(doc: &mut String) {
     doc.truncate(doc.trim_end_matches(' ').len());
 }
@@ -285,3 +509,72 @@ fn can_be_block_comment(value: &str) -> bool {
 
     depth == 0
 }
+
+// Attributes are compared by path, then by how they print with the default
+// configuration so that the order does not depend on other options.
+fn attr_sort_key(parent: &Printer, attr: &Attribute) -> (String, String) {
+    let path = attr
+        .path()
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<String>>()
+        .join("::");
+    let mut rendered = String::new();
+    let mut p = Printer::new(Config::default(), &mut rendered);
+    p.nest_within(parent);
+    p.cbox(0);
+    p.attr(attr);
+    p.end();
+    p.eof();
+    (path, rendered)
+}
+
+fn lint_level(meta: &MetaList) -> String {
+    match meta.path.get_ident() {
+        Some(ident) => ident.to_string(),
+        None => String::new(),
+    }
+}
+
+fn is_lint_attribute(meta: &MetaList) -> bool {
+    match lint_level(meta).as_str() {
+        "allow" | "expect" | "warn" | "deny" | "forbid" => {
+            matches!(meta.delimiter, MacroDelimiter::Paren(_))
+        }
+        _ => false,
+    }
+}
+
+// The lints of `allow(b, a::c, d)` in order of their path, or None if the list
+// contains anything other than comma-separated paths.
+fn sorted_lints(tokens: &TokenStream) -> Option<TokenStream> {
+    let mut lints = vec![Vec::new()];
+    for token in tokens.clone() {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                if lints.last().unwrap().is_empty() {
+                    return None;
+                }
+                lints.push(Vec::new());
+            }
+            TokenTree::Punct(punct) if punct.as_char() == ':' => {
+                lints.last_mut().unwrap().push(token);
+            }
+            TokenTree::Ident(_) => lints.last_mut().unwrap().push(token),
+            _ => return None,
+        }
+    }
+    if lints.last().unwrap().is_empty() {
+        lints.pop();
+    }
+    lints.sort_by_cached_key(|lint| lint.iter().map(TokenTree::to_string).collect::<String>());
+    let mut sorted = TokenStream::new();
+    for (i, lint) in lints.into_iter().enumerate() {
+        if i > 0 {
+            sorted.extend([TokenTree::Punct(Punct::new(',', Spacing::Alone))]);
+        }
+        sorted.extend(lint);
+    }
+    Some(sorted)
+}
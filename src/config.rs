@@ -0,0 +1,511 @@
+use crate::INDENT;
+use proc_macro2::TokenStream;
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+use syn::Path;
+
+/// Formatting options for [`unparse_with_config`][crate::unparse_with_config].
+///
+/// `Config::default()` produces exactly the same output as
+/// [`unparse`][crate::unparse].
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub(crate) continuation_indent: isize,
+    pub(crate) edition: Edition,
+    pub(crate) reorder_fields: bool,
+    pub(crate) pack_unit_variants: bool,
+    pub(crate) debug_markers: bool,
+    pub(crate) force_multiline_blocks: bool,
+    pub(crate) doc_comment_style: Option<DocStyle>,
+    pub(crate) punctuation: Punctuation,
+    pub(crate) absolute_paths: AbsolutePathStyle,
+    pub(crate) quote_style: QuoteStyle,
+    pub(crate) strip_unit_return: bool,
+    pub(crate) reorder_impl_items: bool,
+    pub(crate) reorder_modules: bool,
+    pub(crate) reorder_pub_use: bool,
+    pub(crate) collapse_use_groups: bool,
+    pub(crate) sort_attributes: bool,
+    pub(crate) sort_lint_attributes: bool,
+    pub(crate) inline_short_where: bool,
+    pub(crate) align_match_arms: bool,
+    pub(crate) format_macro_bodies: bool,
+    pub(crate) format_macro_matchers: bool,
+    pub(crate) strip_empty_statements: bool,
+    pub(crate) struct_lit_heuristic: bool,
+    pub(crate) generics_trailing_comma_heuristic: bool,
+    pub(crate) width_metric: WidthMetric,
+    pub(crate) hex_case: Option<Case>,
+    pub(crate) exponent_case: Option<Case>,
+    pub(crate) empty_block_style: EmptyBlock,
+    pub(crate) range_spacing: RangeSpacing,
+    pub(crate) blank_after_inner_attrs: bool,
+    pub(crate) record_transformations: bool,
+    pub(crate) macro_formatters: Vec<MacroFormatter>,
+}
+
+pub(crate) type FormatMacro = dyn Fn(&TokenStream) -> String + Send + Sync;
+
+#[derive(Clone)]
+pub(crate) struct MacroFormatter {
+    name: String,
+    format: Arc<FormatMacro>,
+}
+
+impl Debug for MacroFormatter {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("MacroFormatter")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Rust edition that the formatted code is meant to be compiled with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Edition {
+    E2015,
+    E2018,
+    E2021,
+    E2024,
+}
+
+/// Spacing around punctuation, for style guides that differ from the usual
+/// Rust conventions. Every field defaults to `false`, which is what rustfmt
+/// does.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct Punctuation {
+    /// `fn f ()` instead of `fn f()` in function signatures.
+    pub space_before_fn_paren: bool,
+    /// `Vec< T >` and `fn f< T >()` instead of `Vec<T>` and `fn f<T>()`.
+    pub space_inside_angle_brackets: bool,
+}
+
+/// Whether paths into the standard library crates `std`, `core` and `alloc`
+/// are written with a leading `::`, as in `::std::mem::swap`.
+///
+/// Only multi-segment paths whose first segment is one of those crates are
+/// affected; paths into other crates cannot be told apart from paths into
+/// modules of the current crate without name resolution.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AbsolutePathStyle {
+    /// Leave paths as written.
+    Preserve,
+    /// Add a leading `::`, as generated code does to avoid being affected by
+    /// a local module of the same name.
+    Global,
+    /// Remove the leading `::`. Only applies when targeting edition 2018 or
+    /// later; in 2015 the leading `::` may be needed to reach the crate root.
+    Relative,
+}
+
+/// Whether string literals are written as raw strings, like `r"C:\dir"`, or
+/// as regular strings with escapes, like `"C:\\dir"`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum QuoteStyle {
+    /// Leave string literals as written.
+    Preserve,
+    /// Rewrite a regular string as a raw string if that avoids escaping a
+    /// backslash or quote, and it has no other escapes that a raw string
+    /// could not express.
+    PreferRaw,
+    /// Rewrite every raw string as a regular string.
+    PreferEscaped,
+}
+
+/// How the width of text is measured when deciding whether it fits within
+/// the margin.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WidthMetric {
+    /// Bytes of UTF-8. Exact for ASCII, and overestimates the width of any
+    /// other text.
+    Bytes,
+    /// Unicode scalar values, i.e. `char`s.
+    Chars,
+    /// Columns taken up on a terminal: 2 for East Asian wide and fullwidth
+    /// characters, 0 for combining marks and other zero width characters, 1
+    /// for everything else.
+    ///
+    /// This is an approximation of the Unicode East Asian Width property,
+    /// built into the crate instead of depending on a full Unicode table. It
+    /// covers Hangul, CJK ideographs, kana, fullwidth forms and the common
+    /// emoji blocks. Characters of ambiguous width count as 1, and each
+    /// character of an emoji sequence joined by U+200D counts on its own, so
+    /// such text may come out narrower than a terminal shows it.
+    UnicodeWidth,
+}
+
+/// How an empty pair of braces is written, such as an empty function body,
+/// block, struct, impl or match.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EmptyBlock {
+    /// `{}`
+    Compact,
+    /// `{ }`
+    Spaced,
+}
+
+/// How the operator of a range with both bounds is spaced, in expressions
+/// like `0..n` and patterns like `0..=9` alike.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RangeSpacing {
+    /// `0..=9`
+    Compact,
+    /// `0 ..= 9`
+    Spaced,
+}
+
+/// Letter case for the letters of numeric literals.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Case {
+    Lower,
+    Upper,
+}
+
+/// How `#[doc = "..."]` attributes are written out as doc comments.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DocStyle {
+    /// `/// ...`, one comment line per line of documentation.
+    LineComments,
+    /// `/** ... */`, one comment per attribute.
+    BlockComments,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Config::default()
+    }
+
+    /// Number of spaces by which a wrapped expression, such as a method chain,
+    /// binary operator, or argument list, is indented relative to the line it
+    /// continues. Block contents are always indented by 4 regardless.
+    pub fn continuation_indent(mut self, indent: usize) -> Self {
+        self.continuation_indent = indent as isize;
+        self
+    }
+
+    /// Edition the output targets. Bare trait objects are only left without
+    /// `dyn` when targeting 2015; later editions always get `dyn`.
+    pub fn edition(mut self, edition: Edition) -> Self {
+        self.edition = edition;
+        self
+    }
+
+    /// Print the named fields of structs, unions and enum variants in
+    /// alphabetical order. Types with a `repr` attribute or a derived
+    /// `PartialOrd`/`Ord`, whose behavior depends on field order, are left as
+    /// written.
+    pub fn reorder_fields(mut self, reorder: bool) -> Self {
+        self.reorder_fields = reorder;
+        self
+    }
+
+    /// Print an enum whose variants are all unit variants, without attributes
+    /// and with at most a literal discriminant, with as many variants on each
+    /// line as fit instead of one per line.
+    pub fn pack_unit_variants(mut self, pack: bool) -> Self {
+        self.pack_unit_variants = pack;
+        self
+    }
+
+    /// Print the members of impl blocks and traits in the order associated
+    /// consts, associated types, then functions, keeping the relative order of
+    /// members of the same kind. Members with a `cfg` or `cfg_attr` attribute,
+    /// and macro invocations, stay where they are and nothing is moved past
+    /// them.
+    pub fn reorder_impl_items(mut self, reorder: bool) -> Self {
+        self.reorder_impl_items = reorder;
+        self
+    }
+
+    /// Print the items of inline `mod` blocks in the order extern crates,
+    /// imports, then named items alphabetically, then impl blocks and extern
+    /// blocks, keeping imports in their original order. Items with a `cfg` or
+    /// `cfg_attr` attribute, macro invocations, and re-exports (see
+    /// [`reorder_pub_use`][Config::reorder_pub_use]) stay where they are and
+    /// nothing is moved past them.
+    pub fn reorder_modules(mut self, reorder: bool) -> Self {
+        self.reorder_modules = reorder;
+        self
+    }
+
+    /// Let [`reorder_modules`][Config::reorder_modules] move `pub use`
+    /// re-exports along with the other imports. By default a re-export stays
+    /// where it is, like an item with a `cfg` attribute.
+    pub fn reorder_pub_use(mut self, reorder: bool) -> Self {
+        self.reorder_pub_use = reorder;
+        self
+    }
+
+    /// Print a `use` group that imports a single path without its braces, as
+    /// in `use a::b;` for `use a::{b};`. A lone `self` keeps its braces,
+    /// because `use a::{self};` imports `a` only as a module and not also as
+    /// a function or value of the same name.
+    pub fn collapse_use_groups(mut self, collapse: bool) -> Self {
+        self.collapse_use_groups = collapse;
+        self
+    }
+
+    /// Print the outer attributes of each item, field, statement and
+    /// expression in a deterministic order: doc comments first, in their
+    /// original relative order, then every other attribute sorted by its path
+    /// and then by its printed form.
+    pub fn sort_attributes(mut self, sort: bool) -> Self {
+        self.sort_attributes = sort;
+        self
+    }
+
+    /// Sort the lint names inside `allow`, `expect`, `warn`, `deny` and
+    /// `forbid` attributes, as in `#[allow(dead_code, unused_imports)]`.
+    pub fn sort_lint_attributes(mut self, sort: bool) -> Self {
+        self.sort_lint_attributes = sort;
+        self
+    }
+
+    /// Line up the `=>` of consecutive match arms that each fit on one line
+    /// with an expression body, by padding their patterns. Arms with a block
+    /// body, a pattern wider than half the margin, or that do not fit on one
+    /// line once padded end the run of aligned arms.
+    pub fn align_match_arms(mut self, align: bool) -> Self {
+        self.align_match_arms = align;
+        self
+    }
+
+    /// Keep the where-clause of a function, impl, trait, struct or enum on the
+    /// line of its signature, as in `fn f<T>(t: T) where T: Clone {`, if the
+    /// signature and where-clause together fit on one line. Otherwise the
+    /// where-clause is broken with one predicate per line as usual.
+    ///
+    /// On by default. Turn it off to give every where-clause lines of its own,
+    /// as rustfmt does by default.
+    pub fn inline_short_where(mut self, inline: bool) -> Self {
+        self.inline_short_where = inline;
+        self
+    }
+
+    /// Format the contents of invocations of macros whose syntax is known, as
+    /// Rust code instead of as a sequence of tokens. Currently this lays out
+    /// `vec![...]` like an array expression, with the `verbatim` feature.
+    /// Off by default.
+    pub fn format_macro_bodies(mut self, format: bool) -> Self {
+        self.format_macro_bodies = format;
+        self
+    }
+
+    /// Lay out a `macro_rules!` matcher that does not fit on one line with one
+    /// comma-separated fragment per line, like the parameters of a function,
+    /// instead of filling each line with as many tokens as fit. Off by
+    /// default.
+    pub fn format_macro_matchers(mut self, format: bool) -> Self {
+        self.format_macro_matchers = format;
+        self
+    }
+
+    /// Keep a struct literal broken over multiple lines, one field per line,
+    /// if the syntax tree has a trailing comma after its last field, as
+    /// parsed from a struct literal that was written that way. This keeps
+    /// diffs small when such a literal shrinks enough to fit on one line.
+    pub fn struct_lit_heuristic(mut self, heuristic: bool) -> Self {
+        self.struct_lit_heuristic = heuristic;
+        self
+    }
+
+    /// Keep a list of generic parameters or generic arguments broken over
+    /// multiple lines, one per line, if the syntax tree has a trailing comma
+    /// after its last element, like
+    /// [`struct_lit_heuristic`][Config::struct_lit_heuristic] does for struct
+    /// literals.
+    pub fn generics_trailing_comma_heuristic(mut self, heuristic: bool) -> Self {
+        self.generics_trailing_comma_heuristic = heuristic;
+        self
+    }
+
+    /// How the width of text is measured against the margin. See
+    /// [`WidthMetric`] for the options. The default is bytes.
+    pub fn width_metric(mut self, metric: WidthMetric) -> Self {
+        self.width_metric = metric;
+        self
+    }
+
+    /// How empty braces are written. See [`EmptyBlock`] for the options. The
+    /// default is `{}`.
+    pub fn empty_block_style(mut self, style: EmptyBlock) -> Self {
+        self.empty_block_style = style;
+        self
+    }
+
+    /// How the operator of a range is spaced. See [`RangeSpacing`] for the
+    /// options. The default is `0..=9`.
+    pub fn range_spacing(mut self, spacing: RangeSpacing) -> Self {
+        self.range_spacing = spacing;
+        self
+    }
+
+    /// Leave a blank line between the inner attributes of a file or inline
+    /// module, such as `#![allow(...)]`, and its first item. On by default.
+    pub fn blank_after_inner_attrs(mut self, blank: bool) -> Self {
+        self.blank_after_inner_attrs = blank;
+        self
+    }
+
+    /// Never put the contents of a non-empty block on the same line as its
+    /// braces, as in `|| { f() }` or `unsafe { f() }`, and keep single
+    /// expression match arm blocks as blocks instead of unwrapping them.
+    pub fn force_multiline_blocks(mut self, force: bool) -> Self {
+        self.force_multiline_blocks = force;
+        self
+    }
+
+    /// Write doc attributes in the given comment style. By default, single
+    /// line docs are written as `///` and multi-line docs as `/** */`.
+    ///
+    /// A doc attribute whose text cannot be expressed in the requested style
+    /// falls back to the default.
+    pub fn doc_comment_style(mut self, style: DocStyle) -> Self {
+        self.doc_comment_style = Some(style);
+        self
+    }
+
+    /// Spacing around punctuation. See [`Punctuation`] for the options.
+    pub fn punctuation(mut self, punctuation: Punctuation) -> Self {
+        self.punctuation = punctuation;
+        self
+    }
+
+    /// Rewrite the leading `::` of paths into the standard library crates. See
+    /// [`AbsolutePathStyle`] for the options. The default is to preserve them.
+    pub fn absolute_paths(mut self, style: AbsolutePathStyle) -> Self {
+        self.absolute_paths = style;
+        self
+    }
+
+    /// Rewrite string literals between raw and regular form. See
+    /// [`QuoteStyle`] for the options. The default is to preserve them.
+    pub fn quote_style(mut self, style: QuoteStyle) -> Self {
+        self.quote_style = style;
+        self
+    }
+
+    /// Omit an explicit `-> ()` return type from functions, function pointer
+    /// types and `Fn` trait bounds, as rustfmt does. On by default.
+    pub fn strip_unit_return(mut self, strip: bool) -> Self {
+        self.strip_unit_return = strip;
+        self
+    }
+
+    /// Remove empty statements, i.e. a `;` standing on its own, from blocks.
+    /// The semicolon that ends an expression statement is not affected. On
+    /// by default.
+    pub fn strip_empty_statements(mut self, strip: bool) -> Self {
+        self.strip_empty_statements = strip;
+        self
+    }
+
+    /// Format the body of invocations of the macro `name` with `format`, which
+    /// receives the tokens between the delimiters and returns the text to
+    /// print in their place. A multi-line result is indented as a block inside
+    /// the delimiters.
+    ///
+    /// `name` is matched against the macro's path as written, like
+    /// `"sqlx::query"`, or against just its last segment if `name` has no
+    /// `::`, like `"query"`. Formatters registered later take precedence.
+    pub fn register_macro<F>(mut self, name: &str, format: F) -> Self
+    where
+        F: Fn(&TokenStream) -> String + Send + Sync + 'static,
+    {
+        self.macro_formatters.push(MacroFormatter {
+            name: name.to_owned(),
+            format: Arc::new(format),
+        });
+        self
+    }
+
+    pub(crate) fn macro_formatter(&self, path: &Path) -> Option<Arc<FormatMacro>> {
+        let segments: Vec<String> = path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect();
+        let full = segments.join("::");
+        let last = segments.last()?;
+        self.macro_formatters
+            .iter()
+            .rev()
+            .find(|formatter| {
+                formatter.name == full || !formatter.name.contains("::") && formatter.name == *last
+            })
+            .map(|formatter| Arc::clone(&formatter.format))
+    }
+
+    /// Write the digits of hexadecimal integer literals, like `0xDEADBEEF`, in
+    /// the given case. The `0x` prefix and any suffix are left as written. By
+    /// default the digits are left as written too.
+    pub fn hex_case(mut self, case: Case) -> Self {
+        self.hex_case = Some(case);
+        self
+    }
+
+    /// Write the exponent marker of float literals, like `1.5e10`, in the
+    /// given case. By default it is left as written.
+    pub fn exponent_case(mut self, case: Case) -> Self {
+        self.exponent_case = Some(case);
+        self
+    }
+
+    /// Keep a record of every change made to the code by the options that
+    /// rewrite it rather than only lay it out, for
+    /// [`unparse_with_report`][crate::unparse_with_report] to return.
+    pub fn record_transformations(mut self, record: bool) -> Self {
+        self.record_transformations = record;
+        self
+    }
+
+    /// Annotate the output with the printer's box and break structure: `«»`
+    /// around consistent boxes, `‹›` around inconsistent boxes, and `·` at
+    /// every break that was not taken. The result is not valid Rust; it is
+    /// meant for debugging layout decisions.
+    pub fn debug_markers(mut self, debug: bool) -> Self {
+        self.debug_markers = debug;
+        self
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            continuation_indent: INDENT,
+            edition: Edition::E2024,
+            reorder_fields: false,
+            pack_unit_variants: false,
+            debug_markers: false,
+            force_multiline_blocks: false,
+            doc_comment_style: None,
+            punctuation: Punctuation::default(),
+            absolute_paths: AbsolutePathStyle::Preserve,
+            quote_style: QuoteStyle::Preserve,
+            strip_unit_return: true,
+            reorder_impl_items: false,
+            reorder_modules: false,
+            reorder_pub_use: false,
+            collapse_use_groups: false,
+            sort_attributes: false,
+            sort_lint_attributes: false,
+            inline_short_where: true,
+            align_match_arms: false,
+            format_macro_bodies: false,
+            format_macro_matchers: false,
+            strip_empty_statements: true,
+            struct_lit_heuristic: false,
+            generics_trailing_comma_heuristic: false,
+            width_metric: WidthMetric::Bytes,
+            hex_case: None,
+            exponent_case: None,
+            empty_block_style: EmptyBlock::Compact,
+            range_spacing: RangeSpacing::Compact,
+            blank_after_inner_attrs: true,
+            record_transformations: false,
+            macro_formatters: Vec::new(),
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::algorithm::{self, BeginToken, BreakToken, Breaks, Printer};
 use std::borrow::Cow;
 
-impl Printer {
+impl Printer<'_> {
     pub fn ibox(&mut self, indent: isize) {
         self.scan_begin(BeginToken {
             offset: indent,
@@ -95,4 +95,20 @@ impl Printer {
             ..BreakToken::default()
         });
     }
+    // Lays out some syntax on its own, starting at column `indent` of a line,
+    // to find out how it would print there.
+    pub fn render(&self, indent: usize, print: impl FnOnce(&mut Printer)) -> String {
+        let mut config = self.config.clone();
+        config.debug_markers = false;
+        config.record_transformations = false;
+        let mut out = String::new();
+        let mut p = Printer::new(config, &mut out);
+        p.start_at(indent);
+        p.nest_within(self);
+        p.cbox(0);
+        print(&mut p);
+        p.end();
+        p.eof();
+        out
+    }
 }
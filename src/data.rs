@@ -2,10 +2,16 @@ use crate::algorithm::Printer;
 use crate::iter::IterDelimited;
 use crate::path::PathKind;
 use crate::INDENT;
-use syn::{Field, Fields, FieldsUnnamed, Variant, VisRestricted, Visibility};
+use proc_macro2::TokenTree;
+use std::ptr;
+use syn::punctuated::Punctuated;
+use syn::{
+    Attribute, Expr, Field, Fields, FieldsNamed, FieldsUnnamed, Ident, Meta, Token, UnOp, Variant,
+    VisRestricted, Visibility,
+};
 
-impl Printer {
-    pub fn variant(&mut self, variant: &Variant) {
+impl Printer<'_> {
+    pub fn variant(&mut self, variant: &Variant, reorder_fields: bool) {
         self.outer_attrs(&variant.attrs);
         self.ident(&variant.ident);
         match &variant.fields {
@@ -13,8 +19,9 @@ impl Printer {
                 self.nbsp();
                 self.word("{");
                 self.cbox(INDENT);
-                self.space();
-                for field in fields.named.iter().delimited() {
+                self.space_if_nonempty();
+                let fields = self.named_fields(&variant.ident, fields, reorder_fields);
+                for field in fields.into_iter().delimited() {
                     self.field(&field);
                     self.trailing_comma_or_space(field.is_last);
                 }
@@ -29,12 +36,28 @@ impl Printer {
             }
             Fields::Unit => {}
         }
+        // A discriminant on a variant with fields is printed as is. It is
+        // accepted by the compiler as long as the enum has a primitive repr,
+        // and dropping it would silently change the enum's layout.
         if let Some((_eq_token, discriminant)) = &variant.discriminant {
             self.word(" = ");
             self.expr(discriminant);
         }
     }
 
+    pub fn packed_unit_variants(&mut self, variants: &Punctuated<Variant, Token![,]>) {
+        self.ibox(0);
+        for variant in variants.iter().delimited() {
+            self.variant(&variant, false);
+            self.word(",");
+            if !variant.is_last {
+                self.space();
+            }
+        }
+        self.end();
+        self.hardbreak();
+    }
+
     pub fn fields_unnamed(&mut self, fields: &FieldsUnnamed) {
         self.word("(");
         self.zerobreak();
@@ -46,6 +69,32 @@ impl Printer {
         self.word(")");
     }
 
+    // Whether the named fields of a struct or enum with these attributes may be
+    // alphabetized, i.e. the user asked for it and the field order does not
+    // affect the type's layout or derived ordering.
+    pub fn reorder_fields(&self, attrs: &[Attribute]) -> bool {
+        self.config.reorder_fields && !attrs.iter().any(pins_field_order)
+    }
+
+    pub fn named_fields<'f>(
+        &mut self,
+        owner: &Ident,
+        fields: &'f FieldsNamed,
+        reorder: bool,
+    ) -> Vec<&'f Field> {
+        let ordered = named_fields(fields, reorder);
+        let moved = ordered
+            .iter()
+            .zip(&fields.named)
+            .any(|(field, original)| !ptr::eq(*field, original));
+        if moved {
+            self.record("reorder_fields", || {
+                format!("reordered the fields of `{}`", owner)
+            });
+        }
+        ordered
+    }
+
     pub fn field(&mut self, field: &Field) {
         self.outer_attrs(&field.attrs);
         self.visibility(&field.vis);
@@ -76,3 +125,44 @@ impl Printer {
         self.word(") ");
     }
 }
+
+fn named_fields(fields: &FieldsNamed, reorder: bool) -> Vec<&Field> {
+    let mut fields: Vec<&Field> = fields.named.iter().collect();
+    if reorder {
+        fields.sort_by_cached_key(|field| {
+            let ident = field.ident.as_ref().unwrap().to_string();
+            ident.trim_start_matches("r#").to_owned()
+        });
+    }
+    fields
+}
+
+fn pins_field_order(attr: &Attribute) -> bool {
+    match &attr.meta {
+        Meta::List(meta) if meta.path.is_ident("repr") => true,
+        Meta::List(meta) if meta.path.is_ident("derive") => {
+            meta.tokens.clone().into_iter().any(|token| match token {
+                TokenTree::Ident(ident) => ident == "PartialOrd" || ident == "Ord",
+                _ => false,
+            })
+        }
+        _ => false,
+    }
+}
+
+pub fn is_short_unit_variant(variant: &Variant) -> bool {
+    variant.attrs.is_empty()
+        && matches!(variant.fields, Fields::Unit)
+        && match &variant.discriminant {
+            None => true,
+            Some((_eq_token, discriminant)) => is_literal(discriminant),
+        }
+}
+
+fn is_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(_) => true,
+        Expr::Unary(expr) => matches!(expr.op, UnOp::Neg(_)) && is_literal(&expr.expr),
+        _ => false,
+    }
+}
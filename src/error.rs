@@ -0,0 +1,28 @@
+use crate::MAX_DEPTH;
+use std::fmt::{self, Display};
+
+/// Error returned by [`try_unparse_checked`][crate::try_unparse_checked] when
+/// the syntax tree is too deeply nested to be printed without risking a stack
+/// overflow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    _private: (),
+}
+
+impl Error {
+    pub(crate) fn too_deep() -> Self {
+        Error { _private: () }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "syntax tree is nested more than {} levels deep",
+            MAX_DEPTH,
+        )
+    }
+}
+
+impl std::error::Error for Error {}
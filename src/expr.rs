@@ -1,9 +1,12 @@
 use crate::algorithm::{BreakToken, Printer};
 use crate::attr;
+use crate::config::RangeSpacing;
 use crate::iter::IterDelimited;
 use crate::path::PathKind;
+use crate::precedence::Precedence;
 use crate::stmt;
-use crate::INDENT;
+use crate::ty;
+use crate::{INDENT, MARGIN};
 use proc_macro2::TokenStream;
 use syn::punctuated::Punctuated;
 use syn::{
@@ -12,12 +15,15 @@ use syn::{
     ExprField, ExprForLoop, ExprGroup, ExprIf, ExprIndex, ExprInfer, ExprLet, ExprLit, ExprLoop,
     ExprMacro, ExprMatch, ExprMethodCall, ExprParen, ExprPath, ExprRange, ExprReference,
     ExprRepeat, ExprReturn, ExprStruct, ExprTry, ExprTryBlock, ExprTuple, ExprUnary, ExprUnsafe,
-    ExprWhile, ExprYield, FieldValue, Index, Label, Member, RangeLimits, ReturnType, Stmt, Token,
-    UnOp,
+    ExprWhile, ExprYield, FieldValue, Index, Label, Lit, MacroDelimiter, Member, RangeLimits,
+    ReturnType, Stmt, Token, UnOp,
 };
 
-impl Printer {
+impl Printer<'_> {
     pub fn expr(&mut self, expr: &Expr) {
+        if !self.enter_nested() {
+            return;
+        }
         match expr {
             Expr::Array(expr) => self.expr_array(expr),
             Expr::Assign(expr) => self.expr_assign(expr),
@@ -61,6 +67,7 @@ impl Printer {
             #[cfg_attr(all(test, exhaustive), deny(non_exhaustive_omitted_patterns))]
             _ => unimplemented!("unknown Expr"),
         }
+        self.leave_nested();
     }
 
     pub fn expr_beginning_of_line(&mut self, expr: &Expr, beginning_of_line: bool) {
@@ -83,13 +90,29 @@ impl Printer {
             Expr::MethodCall(expr) => self.subexpr_method_call(expr, beginning_of_line, false),
             Expr::Try(expr) => self.subexpr_try(expr, beginning_of_line),
             _ => {
-                self.cbox(-INDENT);
-                self.expr(expr);
+                self.cbox(-self.config.continuation_indent);
+                if needs_paren_before_postfix(expr) {
+                    self.word("(");
+                    self.expr(expr);
+                    self.word(")");
+                } else {
+                    self.expr(expr);
+                }
                 self.end();
             }
         }
     }
 
+    fn postfix_operand(&mut self, expr: &Expr, beginning_of_line: bool) {
+        if needs_paren_before_postfix(expr) {
+            self.word("(");
+            self.expr(expr);
+            self.word(")");
+        } else {
+            self.expr_beginning_of_line(expr, beginning_of_line);
+        }
+    }
+
     fn wrap_exterior_struct(&mut self, expr: &Expr) {
         let needs_paren = contains_exterior_struct_lit(expr);
         if needs_paren {
@@ -124,10 +147,41 @@ impl Printer {
 
     fn expr_assign(&mut self, expr: &ExprAssign) {
         self.outer_attrs(&expr.attrs);
-        self.ibox(0);
-        self.expr(&expr.left);
-        self.word(" = ");
-        self.expr(&expr.right);
+        self.assign(&expr.left, None, &expr.right, false);
+    }
+
+    // The right-hand side of an assignment goes on the next line if it does
+    // not fit on this one, unless it has places of its own to break at, such
+    // as a block, struct literal or argument list. The left-hand side never
+    // breaks.
+    fn assign(&mut self, left: &Expr, op: Option<&BinOp>, right: &Expr, left_paren: bool) {
+        let opens_on_line = opens_on_line(right);
+        if opens_on_line {
+            self.ibox(0);
+        } else {
+            self.ibox(self.config.continuation_indent);
+            self.ibox(-self.config.continuation_indent);
+        }
+        if left_paren {
+            self.word("(");
+        }
+        self.expr(left);
+        if left_paren {
+            self.word(")");
+        }
+        self.word(" ");
+        match op {
+            Some(op) => self.binary_operator(op),
+            None => self.word("="),
+        }
+        if opens_on_line {
+            self.word(" ");
+            self.neverbreak();
+        } else {
+            self.end();
+            self.space();
+        }
+        self.expr(right);
         self.end();
     }
 
@@ -144,28 +198,82 @@ impl Printer {
 
     fn expr_await(&mut self, expr: &ExprAwait, beginning_of_line: bool) {
         self.outer_attrs(&expr.attrs);
-        self.cbox(INDENT);
+        if let Expr::Call(_) | Expr::Path(_) = &*expr.base {
+            // Not a chain; like `?`, nothing to indent a continuation for.
+            self.expr_beginning_of_line(&expr.base, beginning_of_line);
+            self.word(".await");
+            return;
+        }
+        self.cbox(self.config.continuation_indent);
         self.subexpr_await(expr, beginning_of_line);
         self.end();
     }
 
     fn subexpr_await(&mut self, expr: &ExprAwait, beginning_of_line: bool) {
         self.subexpr(&expr.base, beginning_of_line);
-        self.zerobreak_unless_short_ident(beginning_of_line, &expr.base);
+        // `.await` stays attached to the call, path or field it awaits, so
+        // that a wrapped chain has one `.method().await?` step per line and
+        // `foo().await?` is never split as `foo()` and `.await?`.
+        if !matches!(
+            &*expr.base,
+            Expr::MethodCall(_) | Expr::Call(_) | Expr::Path(_) | Expr::Field(_)
+        ) {
+            self.zerobreak_unless_short_ident(beginning_of_line, &expr.base);
+        }
         self.word(".await");
     }
 
     fn expr_binary(&mut self, expr: &ExprBinary) {
         self.outer_attrs(&expr.attrs);
-        self.ibox(INDENT);
-        self.ibox(-INDENT);
-        self.expr(&expr.left);
-        self.end();
-        self.space();
-        self.binary_operator(&expr.op);
-        self.nbsp();
-        self.expr(&expr.right);
-        self.end();
+        let (left_paren, right_paren) = binary_operand_parens(expr);
+        if Precedence::of_binop(&expr.op) == Precedence::Assign && !right_paren {
+            // Compound assignment breaks after the operator, like `=`.
+            self.assign(&expr.left, Some(&expr.op), &expr.right, left_paren);
+            return;
+        }
+        // A chain like `a + b + c` nests to the left. Print all of its left
+        // operands in one pass, instead of recursing once per operator, so
+        // that a long flat chain does not count as deep nesting.
+        let mut chain = vec![(expr, right_paren)];
+        let mut innermost = expr;
+        let mut left_paren = left_paren;
+        while let (Expr::Binary(left), false) = (&*innermost.left, left_paren) {
+            let (left_left_paren, left_right_paren) = binary_operand_parens(left);
+            if Precedence::of_binop(&left.op) == Precedence::Assign && !left_right_paren {
+                break;
+            }
+            chain.push((left, left_right_paren));
+            innermost = left;
+            left_paren = left_left_paren;
+        }
+        for (i, (expr, _)) in chain.iter().enumerate() {
+            if i > 0 {
+                self.outer_attrs(&expr.attrs);
+            }
+            self.ibox(self.config.continuation_indent);
+            self.ibox(-self.config.continuation_indent);
+        }
+        if left_paren {
+            self.word("(");
+        }
+        self.expr(&innermost.left);
+        if left_paren {
+            self.word(")");
+        }
+        for (expr, right_paren) in chain.iter().rev() {
+            self.end();
+            self.space();
+            self.binary_operator(&expr.op);
+            self.nbsp();
+            if *right_paren {
+                self.word("(");
+            }
+            self.expr(&expr.right);
+            if *right_paren {
+                self.word(")");
+            }
+            self.end();
+        }
     }
 
     pub fn expr_block(&mut self, expr: &ExprBlock) {
@@ -186,14 +294,21 @@ impl Printer {
             self.lifetime(lifetime);
         }
         if let Some(value) = &expr.expr {
-            self.nbsp();
-            self.expr(value);
+            if opens_on_line(value) {
+                self.nbsp();
+                self.expr(value);
+            } else {
+                self.ibox(self.config.continuation_indent);
+                self.space();
+                self.expr(value);
+                self.end();
+            }
         }
     }
 
     fn expr_call(&mut self, expr: &ExprCall, beginning_of_line: bool) {
         self.outer_attrs(&expr.attrs);
-        self.expr_beginning_of_line(&expr.func, beginning_of_line);
+        self.postfix_operand(&expr.func, beginning_of_line);
         self.word("(");
         self.call_args(&expr.args);
         self.word(")");
@@ -208,9 +323,16 @@ impl Printer {
 
     fn expr_cast(&mut self, expr: &ExprCast) {
         self.outer_attrs(&expr.attrs);
-        self.ibox(INDENT);
-        self.ibox(-INDENT);
-        self.expr(&expr.expr);
+        self.ibox(self.config.continuation_indent);
+        self.ibox(-self.config.continuation_indent);
+        if Precedence::of(&expr.expr) < Precedence::Cast {
+            // ERROR CORRECTION: as for prefix operators, in `(a + b) as u8`.
+            self.word("(");
+            self.expr(&expr.expr);
+            self.word(")");
+        } else {
+            self.expr(&expr.expr);
+        }
         self.end();
         self.space();
         self.word("as ");
@@ -242,18 +364,21 @@ impl Printer {
             if pat.is_first {
                 self.zerobreak();
             }
-            self.pat(&pat);
+            self.pat_no_top_alt(&pat);
             if !pat.is_last {
                 self.word(",");
                 self.space();
             }
         }
+        if !expr.inputs.is_empty() {
+            self.trailing_comma(true);
+            self.offset(-INDENT);
+        }
+        self.word("|");
+        self.end();
         match &expr.output {
             ReturnType::Default => {
-                self.word("|");
-                self.space();
-                self.offset(-INDENT);
-                self.end();
+                self.nbsp();
                 self.neverbreak();
                 let wrap_in_brace = match &*expr.body {
                     Expr::Match(ExprMatch { attrs, .. }) | Expr::Call(ExprCall { attrs, .. }) => {
@@ -279,13 +404,20 @@ impl Printer {
                     self.expr(&expr.body);
                 }
             }
-            ReturnType::Type(_arrow, ty) => {
-                if !expr.inputs.is_empty() {
-                    self.trailing_comma(true);
-                    self.offset(-INDENT);
-                }
-                self.word("|");
+            ReturnType::Type(_arrow, ty) if ty::returns_bound_list(&expr.output) => {
+                // Break between the bounds of the return type before breaking
+                // the parameters, and if it does break, put the body's `{` on
+                // a line of its own.
+                self.neverbreak();
+                self.word(" -> ");
+                self.cbox(0);
+                self.ty(ty);
+                self.space();
                 self.end();
+                self.neverbreak();
+                self.expr(&expr.body);
+            }
+            ReturnType::Type(_arrow, ty) => {
                 self.word(" -> ");
                 self.ty(ty);
                 self.nbsp();
@@ -315,14 +447,26 @@ impl Printer {
 
     fn expr_field(&mut self, expr: &ExprField, beginning_of_line: bool) {
         self.outer_attrs(&expr.attrs);
-        self.cbox(INDENT);
+        self.cbox(self.config.continuation_indent);
         self.subexpr_field(expr, beginning_of_line);
         self.end();
     }
 
     fn subexpr_field(&mut self, expr: &ExprField, beginning_of_line: bool) {
-        self.subexpr(&expr.base, beginning_of_line);
-        self.zerobreak_unless_short_ident(beginning_of_line, &expr.base);
+        if let (Member::Unnamed(_), true) = (&expr.member, is_unsuffixed_int(&expr.base)) {
+            // ERROR CORRECTION: `1.0` would be a float literal, so an integer
+            // whose tuple field is accessed needs parentheses.
+            self.word("(");
+            self.expr(&expr.base);
+            self.word(")");
+        } else {
+            self.subexpr(&expr.base, beginning_of_line);
+            // A tuple index stays attached to the field or expression it
+            // indexes, as in `self.pair.0`.
+            if let Member::Named(_) = expr.member {
+                self.zerobreak_unless_short_ident(beginning_of_line, &expr.base);
+            }
+        }
         self.word(".");
         self.member(&expr.member);
     }
@@ -343,8 +487,8 @@ impl Printer {
         self.cbox(INDENT);
         self.hardbreak_if_nonempty();
         self.inner_attrs(&expr.attrs);
-        for stmt in &expr.body.stmts {
-            self.stmt(stmt);
+        for stmt in expr.body.stmts.iter().delimited() {
+            self.stmt(&stmt, stmt.is_last);
         }
         self.offset(-INDENT);
         self.end();
@@ -400,12 +544,14 @@ impl Printer {
                 break;
             }
         } else if expr.then_branch.stmts.is_empty() {
-            self.word("{}");
+            self.word("{");
+            self.empty_block();
+            self.word("}");
         } else {
             self.word("{");
             self.hardbreak();
-            for stmt in &expr.then_branch.stmts {
-                self.stmt(stmt);
+            for stmt in expr.then_branch.stmts.iter().delimited() {
+                self.stmt(&stmt, stmt.is_last);
             }
             self.offset(-INDENT);
             self.word("}");
@@ -415,7 +561,7 @@ impl Printer {
 
     fn expr_index(&mut self, expr: &ExprIndex, beginning_of_line: bool) {
         self.outer_attrs(&expr.attrs);
-        self.expr_beginning_of_line(&expr.expr, beginning_of_line);
+        self.postfix_operand(&expr.expr, beginning_of_line);
         self.word("[");
         self.expr(&expr.index);
         self.word("]");
@@ -440,8 +586,8 @@ impl Printer {
         self.ibox(-INDENT);
         self.pat(&expr.pat);
         self.end();
+        self.word(" =");
         self.space();
-        self.word("= ");
         let needs_paren = contains_exterior_struct_lit(&expr.expr);
         if needs_paren {
             self.word("(");
@@ -467,8 +613,8 @@ impl Printer {
         self.cbox(INDENT);
         self.hardbreak_if_nonempty();
         self.inner_attrs(&expr.attrs);
-        for stmt in &expr.body.stmts {
-            self.stmt(stmt);
+        for stmt in expr.body.stmts.iter().delimited() {
+            self.stmt(&stmt, stmt.is_last);
         }
         self.offset(-INDENT);
         self.end();
@@ -490,8 +636,14 @@ impl Printer {
         self.cbox(INDENT);
         self.hardbreak_if_nonempty();
         self.inner_attrs(&expr.attrs);
-        for arm in &expr.arms {
-            self.arm(arm);
+        let padding = if self.config.align_match_arms && !expr.arms.is_empty() {
+            let indent = self.indent_after_hardbreak();
+            self.arm_padding(&expr.arms, indent)
+        } else {
+            vec![0; expr.arms.len()]
+        };
+        for (arm, padding) in expr.arms.iter().zip(padding) {
+            self.arm(arm, padding);
             self.hardbreak();
         }
         self.offset(-INDENT);
@@ -502,8 +654,8 @@ impl Printer {
 
     fn expr_method_call(&mut self, expr: &ExprMethodCall, beginning_of_line: bool) {
         self.outer_attrs(&expr.attrs);
-        self.cbox(INDENT);
-        let unindent_call_args = beginning_of_line && is_short_ident(&expr.receiver);
+        self.cbox(self.config.continuation_indent);
+        let unindent_call_args = beginning_of_line && self.is_short_ident(&expr.receiver);
         self.subexpr_method_call(expr, beginning_of_line, unindent_call_args);
         self.end();
     }
@@ -520,8 +672,15 @@ impl Printer {
         self.ident(&expr.method);
         if let Some(turbofish) = &expr.turbofish {
             self.angle_bracketed_generic_arguments(turbofish, PathKind::Expr);
+            // Let the turbofish fit or break on its own merits, not based on
+            // the length of the argument list after it.
+            self.neverbreak();
         }
-        self.cbox(if unindent_call_args { -INDENT } else { 0 });
+        self.cbox(if unindent_call_args {
+            -self.config.continuation_indent
+        } else {
+            0
+        });
         self.word("(");
         self.call_args(&expr.args);
         self.word(")");
@@ -542,16 +701,33 @@ impl Printer {
 
     pub fn expr_range(&mut self, expr: &ExprRange) {
         self.outer_attrs(&expr.attrs);
+        // Like a binary operator, a range with both bounds breaks before the
+        // operator, which stays attached to the end bound.
+        self.ibox(self.config.continuation_indent);
+        self.ibox(-self.config.continuation_indent);
         if let Some(start) = &expr.start {
             self.expr(start);
         }
+        self.end();
+        let spaced = expr.start.is_some()
+            && expr.end.is_some()
+            && self.config.range_spacing == RangeSpacing::Spaced;
+        if spaced {
+            self.space();
+        } else if expr.start.is_some() && expr.end.is_some() {
+            self.zerobreak();
+        }
         self.word(match expr.limits {
             RangeLimits::HalfOpen(_) => "..",
             RangeLimits::Closed(_) => "..=",
         });
+        if spaced {
+            self.nbsp();
+        }
         if let Some(end) = &expr.end {
             self.expr(end);
         }
+        self.end();
     }
 
     fn expr_reference(&mut self, expr: &ExprReference) {
@@ -560,15 +736,21 @@ impl Printer {
         if expr.mutability.is_some() {
             self.word("mut ");
         }
-        self.expr(&expr.expr);
+        self.prefix_operand(&expr.expr);
     }
 
     fn expr_repeat(&mut self, expr: &ExprRepeat) {
         self.outer_attrs(&expr.attrs);
         self.word("[");
+        self.cbox(INDENT);
+        self.zerobreak();
         self.expr(&expr.expr);
-        self.word("; ");
+        self.word(";");
+        self.space();
         self.expr(&expr.len);
+        self.zerobreak();
+        self.offset(-INDENT);
+        self.end();
         self.word("]");
     }
 
@@ -588,14 +770,22 @@ impl Printer {
         self.qpath(&expr.qself, &expr.path, PathKind::Expr);
         self.end();
         self.word(" {");
+        let has_rest = expr.dot2_token.is_some();
+        if self.config.struct_lit_heuristic && expr.fields.trailing_punct() && !has_rest {
+            self.force_break();
+        }
         self.space_if_nonempty();
         for field_value in expr.fields.iter().delimited() {
             self.field_value(&field_value);
-            self.trailing_comma_or_space(field_value.is_last && expr.rest.is_none());
+            self.trailing_comma_or_space(field_value.is_last && !has_rest);
         }
-        if let Some(rest) = &expr.rest {
+        // Without a base expression, `..` is the rest of a struct pattern
+        // that was parsed as an expression, as on the left of `=`.
+        if has_rest {
             self.word("..");
-            self.expr(rest);
+            if let Some(rest) = &expr.rest {
+                self.expr(rest);
+            }
             self.space();
         }
         self.offset(-INDENT);
@@ -605,7 +795,7 @@ impl Printer {
 
     fn expr_try(&mut self, expr: &ExprTry, beginning_of_line: bool) {
         self.outer_attrs(&expr.attrs);
-        self.expr_beginning_of_line(&expr.expr, beginning_of_line);
+        self.postfix_operand(&expr.expr, beginning_of_line);
         self.word("?");
     }
 
@@ -644,7 +834,20 @@ impl Printer {
     fn expr_unary(&mut self, expr: &ExprUnary) {
         self.outer_attrs(&expr.attrs);
         self.unary_operator(&expr.op);
-        self.expr(&expr.expr);
+        self.prefix_operand(&expr.expr);
+    }
+
+    // ERROR CORRECTION: a syntax tree built in code may have a binary
+    // operator, cast, range or closure directly under a prefix operator, as in
+    // `-(a + b)`, without an `Expr::Paren` to keep it grouped.
+    fn prefix_operand(&mut self, operand: &Expr) {
+        if Precedence::of(operand) < Precedence::Prefix {
+            self.word("(");
+            self.expr(operand);
+            self.word(")");
+        } else {
+            self.expr(operand);
+        }
     }
 
     fn expr_unsafe(&mut self, expr: &ExprUnsafe) {
@@ -727,8 +930,8 @@ impl Printer {
         self.cbox(INDENT);
         self.hardbreak_if_nonempty();
         self.inner_attrs(&expr.attrs);
-        for stmt in &expr.body.stmts {
-            self.stmt(stmt);
+        for stmt in expr.body.stmts.iter().delimited() {
+            self.stmt(&stmt, stmt.is_last);
         }
         self.offset(-INDENT);
         self.end();
@@ -760,19 +963,84 @@ impl Printer {
         }
     }
 
-    fn arm(&mut self, arm: &Arm) {
-        self.outer_attrs(&arm.attrs);
-        self.ibox(0);
+    fn arm_pat(&mut self, arm: &Arm) {
         self.pat(&arm.pat);
         if let Some((_if_token, guard)) = &arm.guard {
             self.word(" if ");
             self.expr(guard);
         }
+    }
+
+    // How much to pad the pattern of each arm so that the `=>` of every run of
+    // consecutive one-line arms with an expression body lines up.
+    fn arm_padding(&self, arms: &[Arm], indent: usize) -> Vec<usize> {
+        let mut widths: Vec<Option<usize>> = arms
+            .iter()
+            .map(|arm| {
+                if !arm.attrs.is_empty() {
+                    return None;
+                }
+                let pat = self.render(indent, |p| p.arm_pat(arm));
+                let width = self.width(pat.trim_end()) as usize;
+                if pat.trim_end().contains('\n') || width > MARGIN as usize / 2 {
+                    return None;
+                }
+                // Block bodies are not followed by a comma.
+                let rendered = self.render(indent, |p| p.arm(arm, 0));
+                let rendered = rendered.trim_end();
+                if rendered.contains('\n') || !rendered.ends_with(',') {
+                    return None;
+                }
+                Some(width)
+            })
+            .collect();
+        loop {
+            let mut padding = vec![0; arms.len()];
+            let mut start = 0;
+            for end in 0..=arms.len() {
+                if end == arms.len() || widths[end].is_none() {
+                    let run = &widths[start..end];
+                    let max = run.iter().flatten().max().copied().unwrap_or(0);
+                    for (i, width) in run.iter().flatten().enumerate() {
+                        padding[start + i] = max - width;
+                    }
+                    start = end + 1;
+                }
+            }
+            // An arm that no longer fits within the margin once padded leaves
+            // its run, which may change the padding of the rest of the run.
+            let mut overflowed = false;
+            for (i, arm) in arms.iter().enumerate() {
+                if padding[i] > 0 {
+                    let rendered = self.render(indent, |p| p.arm(arm, padding[i]));
+                    let rendered = rendered.trim_end();
+                    if rendered.contains('\n')
+                        || indent + self.width(rendered) as usize > MARGIN as usize
+                    {
+                        widths[i] = None;
+                        overflowed = true;
+                    }
+                }
+            }
+            if !overflowed {
+                return padding;
+            }
+        }
+    }
+
+    fn arm(&mut self, arm: &Arm, padding: usize) {
+        self.outer_attrs(&arm.attrs);
+        self.ibox(0);
+        self.arm_pat(arm);
+        if padding > 0 {
+            self.word(" ".repeat(padding));
+        }
         self.word(" =>");
         let empty_block;
         let mut body = &*arm.body;
         while let Expr::Block(expr) = body {
-            if expr.attrs.is_empty() && expr.label.is_none() {
+            if expr.attrs.is_empty() && expr.label.is_none() && !self.config.force_multiline_blocks
+            {
                 let mut stmts = expr.block.stmts.iter();
                 if let (Some(Stmt::Expr(inner, None)), None) = (stmts.next(), stmts.next()) {
                     body = inner;
@@ -804,8 +1072,8 @@ impl Printer {
             self.cbox(INDENT);
             self.hardbreak_if_nonempty();
             self.inner_attrs(&body.attrs);
-            for stmt in &body.block.stmts {
-                self.stmt(stmt);
+            for stmt in body.block.stmts.iter().delimited() {
+                self.stmt(&stmt, stmt.is_last);
             }
             self.offset(-INDENT);
             self.end();
@@ -839,13 +1107,14 @@ impl Printer {
                 self.expr(expr);
             }
             _ => {
-                self.cbox(INDENT);
+                let indent = self.config.continuation_indent;
+                self.cbox(indent);
                 self.zerobreak();
                 for arg in args.iter().delimited() {
                     self.expr(&arg);
                     self.trailing_comma(arg.is_last);
                 }
-                self.offset(-INDENT);
+                self.offset(-indent);
                 self.end();
             }
         }
@@ -854,7 +1123,11 @@ impl Printer {
     pub fn small_block(&mut self, block: &Block, attrs: &[Attribute]) {
         self.word("{");
         if attr::has_inner(attrs) || !block.stmts.is_empty() {
-            self.space();
+            if self.config.force_multiline_blocks {
+                self.hardbreak();
+            } else {
+                self.space();
+            }
             self.inner_attrs(attrs);
             match (block.stmts.get(0), block.stmts.get(1)) {
                 (Some(Stmt::Expr(expr, None)), None) if stmt::break_after(expr) => {
@@ -864,12 +1137,14 @@ impl Printer {
                     self.space();
                 }
                 _ => {
-                    for stmt in &block.stmts {
-                        self.stmt(stmt);
+                    for stmt in block.stmts.iter().delimited() {
+                        self.stmt(&stmt, stmt.is_last);
                     }
                 }
             }
             self.offset(-INDENT);
+        } else {
+            self.empty_block();
         }
         self.word("}");
     }
@@ -931,11 +1206,28 @@ impl Printer {
     }
 
     fn zerobreak_unless_short_ident(&mut self, beginning_of_line: bool, expr: &Expr) {
-        if beginning_of_line && is_short_ident(expr) {
+        if beginning_of_line && self.is_short_ident(expr) {
+            return;
+        }
+        // `}.method()`, not `}` and `.method()` on the next line.
+        if ends_with_brace(expr) {
             return;
         }
         self.zerobreak();
     }
+
+    // A receiver no wider than the continuation indent gains nothing from
+    // being broken away from the method that follows it.
+    fn is_short_ident(&self, expr: &Expr) -> bool {
+        if let Expr::Path(expr) = expr {
+            return expr.attrs.is_empty()
+                && expr.qself.is_none()
+                && expr.path.get_ident().map_or(false, |ident| {
+                    ident.to_string().len() as isize <= self.config.continuation_indent
+                });
+        }
+        false
+    }
 }
 
 pub fn requires_terminator(expr: &Expr) -> bool {
@@ -986,6 +1278,29 @@ pub fn requires_terminator(expr: &Expr) -> bool {
     }
 }
 
+// Whether the value of an assignment or initializer goes on the same line as
+// the `=` no matter what, because it has places of its own to break at, like
+// a delimiter. A binary operation goes by its first operand, which is what
+// has to fit on the line of the `=`.
+pub fn opens_on_line(expr: &Expr) -> bool {
+    match expr {
+        Expr::Reference(ExprReference { expr, .. }) | Expr::Unary(ExprUnary { expr, .. }) => {
+            opens_on_line(expr)
+        }
+        Expr::Binary(ExprBinary { left, .. }) => opens_on_line(left),
+        // A literal spanning multiple lines, like a string with `\`
+        // continuations, is left where it starts.
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Str(lit) => lit.token().to_string().contains('\n'),
+            Lit::ByteStr(lit) => lit.token().to_string().contains('\n'),
+            _ => false,
+        },
+        Expr::Call(expr) => !expr.args.is_empty(),
+        Expr::Field(_) | Expr::Index(_) | Expr::Path(_) | Expr::Range(_) => false,
+        _ => true,
+    }
+}
+
 // Expressions that syntactically contain an "exterior" struct literal i.e. not
 // surrounded by any parens or other delimiters. For example `X { y: 1 }`, `X {
 // y: 1 }.method()`, `foo == X { y: 1 }` and `X { y: 1 } == foo` all do, but `(X
@@ -1099,16 +1414,30 @@ fn needs_newline_if_wrap(expr: &Expr) -> bool {
     }
 }
 
-fn is_short_ident(expr: &Expr) -> bool {
-    if let Expr::Path(expr) = expr {
-        return expr.attrs.is_empty()
-            && expr.qself.is_none()
-            && expr
-                .path
-                .get_ident()
-                .map_or(false, |ident| ident.to_string().len() as isize <= INDENT);
-    }
-    false
+// Whether the left and right operand of a binary expression need parentheses
+// to keep the tree from being reparsed with a different shape. This only
+// matters for syntax trees constructed by hand; parsed ones carry their
+// parentheses as Expr::Paren.
+pub fn binary_operand_parens(expr: &ExprBinary) -> (bool, bool) {
+    let precedence = Precedence::of_binop(&expr.op);
+    let left = Precedence::of(&expr.left);
+    let right = Precedence::of(&expr.right);
+    let left_paren = match precedence {
+        // Assignment is right associative.
+        Precedence::Assign => left <= precedence,
+        // Comparison operators do not chain: `a == b == c` is an error.
+        Precedence::Compare => left <= precedence,
+        _ => left < precedence,
+    } || match (&*expr.left, &expr.op) {
+        // `a as usize < b` would parse `usize<` as the start of generic args.
+        (Expr::Cast(_), BinOp::Lt(_) | BinOp::Shl(_)) => true,
+        _ => false,
+    };
+    let right_paren = match precedence {
+        Precedence::Assign => right < precedence,
+        _ => right <= precedence,
+    };
+    (left_paren, right_paren)
 }
 
 fn is_blocklike(expr: &Expr) -> bool {
@@ -1158,3 +1487,39 @@ fn is_blocklike(expr: &Expr) -> bool {
         _ => false,
     }
 }
+
+fn ends_with_brace(expr: &Expr) -> bool {
+    match expr {
+        Expr::Async(_)
+        | Expr::Block(_)
+        | Expr::Const(_)
+        | Expr::ForLoop(_)
+        | Expr::If(_)
+        | Expr::Loop(_)
+        | Expr::Match(_)
+        | Expr::Struct(_)
+        | Expr::TryBlock(_)
+        | Expr::Unsafe(_)
+        | Expr::While(_) => true,
+        Expr::Macro(expr) => matches!(expr.mac.delimiter, MacroDelimiter::Brace(_)),
+        _ => false,
+    }
+}
+
+fn is_unsuffixed_int(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Int(lit) => lit.suffix().is_empty(),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// ERROR CORRECTION: a syntax tree built in code may index, call, access a field
+// of or call a method on a range or operator expression, as in `(a..b)[i]`,
+// `(*p).len()` or `(a as u8)?`, without the parentheses that keep the postfix
+// operator from applying to only its last operand.
+fn needs_paren_before_postfix(expr: &Expr) -> bool {
+    Precedence::of(expr) < Precedence::Unambiguous
+}
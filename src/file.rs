@@ -1,7 +1,8 @@
 use crate::algorithm::Printer;
-use syn::File;
+use crate::attr;
+use syn::{Attribute, File, Item};
 
-impl Printer {
+impl Printer<'_> {
     pub fn file(&mut self, file: &File) {
         self.cbox(0);
         if let Some(shebang) = &file.shebang {
@@ -9,9 +10,16 @@ impl Printer {
             self.hardbreak();
         }
         self.inner_attrs(&file.attrs);
+        self.blank_after_inner_attrs(&file.attrs, &file.items);
         for item in &file.items {
             self.item(item);
         }
         self.end();
     }
+
+    pub fn blank_after_inner_attrs(&mut self, attrs: &[Attribute], items: &[Item]) {
+        if self.config.blank_after_inner_attrs && attr::has_inner(attrs) && !items.is_empty() {
+            self.hardbreak();
+        }
+    }
 }
@@ -10,7 +10,7 @@ use syn::{
     WherePredicate,
 };
 
-impl Printer {
+impl Printer<'_> {
     pub fn generics(&mut self, generics: &Generics) {
         if generics.params.is_empty() {
             return;
@@ -18,7 +18,10 @@ impl Printer {
 
         self.word("<");
         self.cbox(0);
-        self.zerobreak();
+        if self.config.generics_trailing_comma_heuristic && generics.params.trailing_punct() {
+            self.force_break();
+        }
+        self.angle_bracket_open_break();
 
         // Print lifetimes before types and consts, regardless of their
         // order in self.params.
@@ -38,7 +41,7 @@ impl Printer {
             for param in &generics.params {
                 if group(param) == current_group {
                     self.generic_param(param);
-                    self.trailing_comma(ptr::eq(param, last.unwrap()));
+                    self.angle_bracket_separator(ptr::eq(param, last.unwrap()));
                 }
             }
         }
@@ -48,6 +51,22 @@ impl Printer {
         self.word(">");
     }
 
+    pub fn angle_bracket_open_break(&mut self) {
+        if self.config.punctuation.space_inside_angle_brackets {
+            self.space();
+        } else {
+            self.zerobreak();
+        }
+    }
+
+    pub fn angle_bracket_separator(&mut self, is_last: bool) {
+        if self.config.punctuation.space_inside_angle_brackets {
+            self.trailing_comma_or_space(is_last);
+        } else {
+            self.trailing_comma(is_last);
+        }
+    }
+
     fn generic_param(&mut self, generic_param: &GenericParam) {
         match generic_param {
             GenericParam::Type(type_param) => self.type_param(type_param),
@@ -125,8 +144,9 @@ impl Printer {
         if let Some(bound_lifetimes) = &trait_bound.lifetimes {
             self.bound_lifetimes(bound_lifetimes);
         }
+        let leading_colon = self.path_leading_colon(&trait_bound.path);
         for segment in trait_bound.path.segments.iter().delimited() {
-            if !segment.is_first || trait_bound.path.leading_colon.is_some() {
+            if !segment.is_first || leading_colon {
                 self.word("::");
             }
             self.path_segment(&segment, PathKind::Type);
@@ -145,7 +165,7 @@ impl Printer {
 
     #[cfg(not(feature = "verbatim"))]
     fn type_param_bound_verbatim(&mut self, bound: &TokenStream) {
-        unimplemented!("TypeParamBound::Verbatim `{}`", bound);
+        self.verbatim_tokens(bound);
     }
 
     #[cfg(feature = "verbatim")]
@@ -175,7 +195,7 @@ impl Printer {
 
         let bound: TypeParamBoundVerbatim = match syn::parse2(tokens.clone()) {
             Ok(bound) => bound,
-            Err(_) => unimplemented!("TypeParamBound::Verbatim `{}`", tokens),
+            Err(_) => return self.verbatim_tokens(tokens),
         };
 
         match bound {
@@ -198,10 +218,27 @@ impl Printer {
         }
     }
 
+    // With inline_short_where, the header of an item with a where-clause and
+    // a body goes in a box of its own, opened here before anything in the
+    // header that might break. The where-clause stays on the line of the
+    // header if all of it fits there, `{` included, and otherwise breaks the
+    // same way as without the option.
+    pub fn where_clause_for_body_begin(&mut self, where_clause: &Option<WhereClause>) {
+        if self.config.inline_short_where && has_predicates(where_clause) {
+            self.cbox(0);
+        }
+    }
+
     pub fn where_clause_for_body(&mut self, where_clause: &Option<WhereClause>) {
-        let hardbreaks = true;
         let semi = false;
-        self.where_clause_impl(where_clause, hardbreaks, semi);
+        if self.config.inline_short_where && has_predicates(where_clause) {
+            let hardbreaks = false;
+            self.where_clause_impl(where_clause, hardbreaks, semi);
+            self.end();
+        } else {
+            let hardbreaks = true;
+            self.where_clause_impl(where_clause, hardbreaks, semi);
+        }
     }
 
     pub fn where_clause_semi(&mut self, where_clause: &Option<WhereClause>) {
@@ -323,3 +360,10 @@ impl Printer {
         self.end();
     }
 }
+
+pub fn has_predicates(where_clause: &Option<WhereClause>) -> bool {
+    match where_clause {
+        Some(where_clause) => !where_clause.predicates.is_empty(),
+        None => false,
+    }
+}
@@ -1,18 +1,24 @@
 use crate::algorithm::Printer;
+use crate::data;
+use crate::expr;
+use crate::generics;
 use crate::iter::IterDelimited;
 use crate::path::PathKind;
+use crate::ty;
 use crate::INDENT;
 use proc_macro2::TokenStream;
+use std::ptr;
 use syn::{
-    Fields, FnArg, ForeignItem, ForeignItemFn, ForeignItemMacro, ForeignItemStatic,
-    ForeignItemType, ImplItem, ImplItemConst, ImplItemFn, ImplItemMacro, ImplItemType, Item,
-    ItemConst, ItemEnum, ItemExternCrate, ItemFn, ItemForeignMod, ItemImpl, ItemMacro, ItemMod,
-    ItemStatic, ItemStruct, ItemTrait, ItemTraitAlias, ItemType, ItemUnion, ItemUse, Receiver,
-    Signature, StaticMutability, TraitItem, TraitItemConst, TraitItemFn, TraitItemMacro,
-    TraitItemType, Type, UseGlob, UseGroup, UseName, UsePath, UseRename, UseTree, Variadic,
+    Attribute, Expr, Fields, FnArg, ForeignItem, ForeignItemFn, ForeignItemMacro,
+    ForeignItemStatic, ForeignItemType, Ident, ImplItem, ImplItemConst, ImplItemFn, ImplItemMacro,
+    ImplItemType, Item, ItemConst, ItemEnum, ItemExternCrate, ItemFn, ItemForeignMod, ItemImpl,
+    ItemMacro, ItemMod, ItemStatic, ItemStruct, ItemTrait, ItemTraitAlias, ItemType, ItemUnion,
+    ItemUse, Receiver, Signature, StaticMutability, TraitItem, TraitItemConst, TraitItemFn,
+    TraitItemMacro, TraitItemType, Type, UseGlob, UseGroup, UseName, UsePath, UseRename, UseTree,
+    Variadic, Visibility,
 };
 
-impl Printer {
+impl Printer<'_> {
     pub fn item(&mut self, item: &Item) {
         match item {
             Item::Const(item) => self.item_const(item),
@@ -38,16 +44,14 @@ impl Printer {
 
     fn item_const(&mut self, item: &ItemConst) {
         self.outer_attrs(&item.attrs);
-        self.cbox(0);
+        self.initializer_begin(Some(&item.expr));
         self.visibility(&item.vis);
         self.word("const ");
         self.ident(&item.ident);
         self.generics(&item.generics);
         self.word(": ");
         self.ty(&item.ty);
-        self.word(" = ");
-        self.neverbreak();
-        self.expr(&item.expr);
+        self.initializer(&item.expr);
         self.word(";");
         self.end();
         self.hardbreak();
@@ -57,16 +61,25 @@ impl Printer {
         self.outer_attrs(&item.attrs);
         self.cbox(INDENT);
         self.visibility(&item.vis);
+        self.where_clause_for_body_begin(&item.generics.where_clause);
         self.word("enum ");
         self.ident(&item.ident);
         self.generics(&item.generics);
         self.where_clause_for_body(&item.generics.where_clause);
         self.word("{");
         self.hardbreak_if_nonempty();
-        for variant in &item.variants {
-            self.variant(variant);
-            self.word(",");
-            self.hardbreak();
+        if self.config.pack_unit_variants
+            && !item.variants.is_empty()
+            && item.variants.iter().all(data::is_short_unit_variant)
+        {
+            self.packed_unit_variants(&item.variants);
+        } else {
+            let reorder_fields = self.reorder_fields(&item.attrs);
+            for variant in &item.variants {
+                self.variant(variant, reorder_fields);
+                self.word(",");
+                self.hardbreak();
+            }
         }
         self.offset(-INDENT);
         self.end();
@@ -91,13 +104,12 @@ impl Printer {
         self.outer_attrs(&item.attrs);
         self.cbox(INDENT);
         self.visibility(&item.vis);
-        self.signature(&item.sig);
-        self.where_clause_for_body(&item.sig.generics.where_clause);
+        self.signature_for_body(&item.sig);
         self.word("{");
         self.hardbreak_if_nonempty();
         self.inner_attrs(&item.attrs);
-        for stmt in &item.block.stmts {
-            self.stmt(stmt);
+        for stmt in item.block.stmts.iter().delimited() {
+            self.stmt(&stmt, stmt.is_last);
         }
         self.offset(-INDENT);
         self.end();
@@ -127,6 +139,7 @@ impl Printer {
     fn item_impl(&mut self, item: &ItemImpl) {
         self.outer_attrs(&item.attrs);
         self.cbox(INDENT);
+        self.where_clause_for_body_begin(&item.generics.where_clause);
         self.ibox(-INDENT);
         self.cbox(INDENT);
         if item.defaultness.is_some() {
@@ -153,7 +166,11 @@ impl Printer {
         self.word("{");
         self.hardbreak_if_nonempty();
         self.inner_attrs(&item.attrs);
-        for impl_item in &item.items {
+        let impl_items = impl_items(&item.items, self.config.reorder_impl_items);
+        self.record_reordered("reorder_impl_items", &impl_items, &item.items, || {
+            "an impl block".to_owned()
+        });
+        for impl_item in impl_items {
             self.impl_item(impl_item);
         }
         self.offset(-INDENT);
@@ -182,7 +199,16 @@ impl Printer {
             self.word(" {");
             self.hardbreak_if_nonempty();
             self.inner_attrs(&item.attrs);
-            for item in items {
+            self.blank_after_inner_attrs(&item.attrs, items);
+            let module_items = module_items(
+                items,
+                self.config.reorder_modules,
+                self.config.reorder_pub_use,
+            );
+            self.record_reordered("reorder_modules", &module_items, items, || {
+                format!("module `{}`", item.ident)
+            });
+            for item in module_items {
                 self.item(item);
             }
             self.offset(-INDENT);
@@ -197,16 +223,14 @@ impl Printer {
 
     fn item_static(&mut self, item: &ItemStatic) {
         self.outer_attrs(&item.attrs);
-        self.cbox(0);
+        self.initializer_begin(Some(&item.expr));
         self.visibility(&item.vis);
         self.word("static ");
         self.static_mutability(&item.mutability);
         self.ident(&item.ident);
         self.word(": ");
         self.ty(&item.ty);
-        self.word(" = ");
-        self.neverbreak();
-        self.expr(&item.expr);
+        self.initializer(&item.expr);
         self.word(";");
         self.end();
         self.hardbreak();
@@ -216,6 +240,9 @@ impl Printer {
         self.outer_attrs(&item.attrs);
         self.cbox(INDENT);
         self.visibility(&item.vis);
+        if let Fields::Named(_) = &item.fields {
+            self.where_clause_for_body_begin(&item.generics.where_clause);
+        }
         self.word("struct ");
         self.ident(&item.ident);
         self.generics(&item.generics);
@@ -224,7 +251,8 @@ impl Printer {
                 self.where_clause_for_body(&item.generics.where_clause);
                 self.word("{");
                 self.hardbreak_if_nonempty();
-                for field in &fields.named {
+                let reorder_fields = self.reorder_fields(&item.attrs);
+                for field in self.named_fields(&item.ident, fields, reorder_fields) {
                     self.field(field);
                     self.word(",");
                     self.hardbreak();
@@ -234,7 +262,10 @@ impl Printer {
                 self.word("}");
             }
             Fields::Unnamed(fields) => {
+                self.neverbreak();
+                self.cbox(0);
                 self.fields_unnamed(fields);
+                self.end();
                 self.where_clause_semi(&item.generics.where_clause);
                 self.end();
             }
@@ -258,20 +289,43 @@ impl Printer {
         }
         self.word("trait ");
         self.ident(&item.ident);
+        self.where_clause_for_body_begin(&item.generics.where_clause);
         self.generics(&item.generics);
-        for supertrait in item.supertraits.iter().delimited() {
-            if supertrait.is_first {
-                self.word(": ");
+        if item.supertraits.is_empty() {
+            self.where_clause_for_body(&item.generics.where_clause);
+        } else {
+            // A supertrait list that does not fit goes on a line of its own,
+            // wrapping before `+`, with the `{` on the next line after it.
+            self.neverbreak();
+            self.word(":");
+            self.cbox(0);
+            self.space();
+            self.ibox(0);
+            for supertrait in item.supertraits.iter().delimited() {
+                if !supertrait.is_first {
+                    self.space();
+                    self.word("+ ");
+                }
+                self.type_param_bound(&supertrait);
+            }
+            self.end();
+            if generics::has_predicates(&item.generics.where_clause) {
+                self.end();
+                self.where_clause_for_body(&item.generics.where_clause);
             } else {
-                self.word(" + ");
+                self.space();
+                self.offset(-INDENT);
+                self.end();
             }
-            self.type_param_bound(&supertrait);
         }
-        self.where_clause_for_body(&item.generics.where_clause);
         self.word("{");
         self.hardbreak_if_nonempty();
         self.inner_attrs(&item.attrs);
-        for trait_item in &item.items {
+        let trait_items = trait_items(&item.items, self.config.reorder_impl_items);
+        self.record_reordered("reorder_impl_items", &trait_items, &item.items, || {
+            format!("trait `{}`", item.ident)
+        });
+        for trait_item in trait_items {
             self.trait_item(trait_item);
         }
         self.offset(-INDENT);
@@ -289,6 +343,9 @@ impl Printer {
         self.generics(&item.generics);
         self.word(" = ");
         self.neverbreak();
+        // The bounds break as a group of their own, independent of whether
+        // the where-clause after them does.
+        self.cbox(0);
         for bound in item.bounds.iter().delimited() {
             if !bound.is_first {
                 self.space();
@@ -296,6 +353,7 @@ impl Printer {
             }
             self.type_param_bound(&bound);
         }
+        self.end();
         self.where_clause_semi(&item.generics.where_clause);
         self.end();
         self.hardbreak();
@@ -323,13 +381,15 @@ impl Printer {
         self.outer_attrs(&item.attrs);
         self.cbox(INDENT);
         self.visibility(&item.vis);
+        self.where_clause_for_body_begin(&item.generics.where_clause);
         self.word("union ");
         self.ident(&item.ident);
         self.generics(&item.generics);
         self.where_clause_for_body(&item.generics.where_clause);
         self.word("{");
         self.hardbreak_if_nonempty();
-        for field in &item.fields.named {
+        let reorder_fields = self.reorder_fields(&item.attrs);
+        for field in self.named_fields(&item.ident, &item.fields, reorder_fields) {
             self.field(field);
             self.word(",");
             self.hardbreak();
@@ -344,7 +404,13 @@ impl Printer {
         self.outer_attrs(&item.attrs);
         self.visibility(&item.vis);
         self.word("use ");
-        if item.leading_colon.is_some() {
+        let leading_colon = match &item.tree {
+            UseTree::Path(use_path) => {
+                self.leading_colon(item.leading_colon.is_some(), &use_path.ident, true)
+            }
+            _ => item.leading_colon.is_some(),
+        };
+        if leading_colon {
             self.word("::");
         }
         self.use_tree(&item.tree);
@@ -581,6 +647,7 @@ impl Printer {
             ItemVerbatim::ImplFlexible(item) => {
                 self.outer_attrs(&item.attrs);
                 self.cbox(INDENT);
+                self.where_clause_for_body_begin(&item.generics.where_clause);
                 self.ibox(-INDENT);
                 self.cbox(INDENT);
                 self.visibility(&item.vis);
@@ -613,7 +680,11 @@ impl Printer {
                 self.word("{");
                 self.hardbreak_if_nonempty();
                 self.inner_attrs(&item.attrs);
-                for impl_item in &item.items {
+                let impl_items = impl_items(&item.items, self.config.reorder_impl_items);
+                self.record_reordered("reorder_impl_items", &impl_items, &item.items, || {
+                    "an impl block".to_owned()
+                });
+                for impl_item in impl_items {
                     self.impl_item(impl_item);
                 }
                 self.offset(-INDENT);
@@ -735,7 +806,10 @@ impl Printer {
     fn use_group(&mut self, use_group: &UseGroup) {
         if use_group.items.is_empty() {
             self.word("{}");
-        } else if use_group.items.len() == 1 {
+        } else if self.config.collapse_use_groups && is_collapsible(use_group) {
+            self.record("collapse_use_groups", || {
+                "removed the braces around a single import".to_owned()
+            });
             self.use_tree(&use_group.items[0]);
         } else {
             self.cbox(INDENT);
@@ -913,16 +987,18 @@ impl Printer {
 
     fn trait_item_const(&mut self, trait_item: &TraitItemConst) {
         self.outer_attrs(&trait_item.attrs);
-        self.cbox(0);
+        let default = trait_item
+            .default
+            .as_ref()
+            .map(|(_eq_token, default)| default);
+        self.initializer_begin(default);
         self.word("const ");
         self.ident(&trait_item.ident);
         self.generics(&trait_item.generics);
         self.word(": ");
         self.ty(&trait_item.ty);
-        if let Some((_eq_token, default)) = &trait_item.default {
-            self.word(" = ");
-            self.neverbreak();
-            self.expr(default);
+        if let Some(default) = default {
+            self.initializer(default);
         }
         self.word(";");
         self.end();
@@ -932,19 +1008,19 @@ impl Printer {
     fn trait_item_fn(&mut self, trait_item: &TraitItemFn) {
         self.outer_attrs(&trait_item.attrs);
         self.cbox(INDENT);
-        self.signature(&trait_item.sig);
         if let Some(block) = &trait_item.default {
-            self.where_clause_for_body(&trait_item.sig.generics.where_clause);
+            self.signature_for_body(&trait_item.sig);
             self.word("{");
             self.hardbreak_if_nonempty();
             self.inner_attrs(&trait_item.attrs);
-            for stmt in &block.stmts {
-                self.stmt(stmt);
+            for stmt in block.stmts.iter().delimited() {
+                self.stmt(&stmt, stmt.is_last);
             }
             self.offset(-INDENT);
             self.end();
             self.word("}");
         } else {
+            self.signature(&trait_item.sig);
             self.where_clause_semi(&trait_item.sig.generics.where_clause);
             self.end();
         }
@@ -1088,7 +1164,7 @@ impl Printer {
 
     fn impl_item_const(&mut self, impl_item: &ImplItemConst) {
         self.outer_attrs(&impl_item.attrs);
-        self.cbox(0);
+        self.initializer_begin(Some(&impl_item.expr));
         self.visibility(&impl_item.vis);
         if impl_item.defaultness.is_some() {
             self.word("default ");
@@ -1098,9 +1174,7 @@ impl Printer {
         self.generics(&impl_item.generics);
         self.word(": ");
         self.ty(&impl_item.ty);
-        self.word(" = ");
-        self.neverbreak();
-        self.expr(&impl_item.expr);
+        self.initializer(&impl_item.expr);
         self.word(";");
         self.end();
         self.hardbreak();
@@ -1113,13 +1187,12 @@ impl Printer {
         if impl_item.defaultness.is_some() {
             self.word("default ");
         }
-        self.signature(&impl_item.sig);
-        self.where_clause_for_body(&impl_item.sig.generics.where_clause);
+        self.signature_for_body(&impl_item.sig);
         self.word("{");
         self.hardbreak_if_nonempty();
         self.inner_attrs(&impl_item.attrs);
-        for stmt in &impl_item.block.stmts {
-            self.stmt(stmt);
+        for stmt in impl_item.block.stmts.iter().delimited() {
+            self.stmt(&stmt, stmt.is_last);
         }
         self.offset(-INDENT);
         self.end();
@@ -1233,7 +1306,44 @@ impl Printer {
         }
     }
 
+    // A signature laid out the way it is above the body of a function, but on
+    // its own, without the body or a semicolon.
+    pub fn standalone_signature(&mut self, signature: &Signature) {
+        self.cbox(INDENT);
+        self.where_clause_for_body_begin(&signature.generics.where_clause);
+        self.signature(signature);
+        if generics::has_predicates(&signature.generics.where_clause) {
+            self.where_clause_for_body(&signature.generics.where_clause);
+        }
+        self.end();
+    }
+
     fn signature(&mut self, signature: &Signature) {
+        self.signature_before_output(signature);
+        self.cbox(-INDENT);
+        self.return_type(&signature.output);
+        self.end();
+    }
+
+    // The signature and where-clause of a function, up to the `{` of its body.
+    fn signature_for_body(&mut self, signature: &Signature) {
+        let where_clause = &signature.generics.where_clause;
+        if generics::has_predicates(where_clause) || !ty::returns_bound_list(&signature.output) {
+            self.where_clause_for_body_begin(where_clause);
+            self.signature(signature);
+            self.where_clause_for_body(where_clause);
+            return;
+        }
+        // If the return type breaks between the bounds of an `impl Trait`,
+        // the `{` goes on a line of its own, as it does after a where-clause.
+        self.signature_before_output(signature);
+        self.cbox(-INDENT);
+        self.return_type(&signature.output);
+        self.space();
+        self.end();
+    }
+
+    fn signature_before_output(&mut self, signature: &Signature) {
         if signature.constness.is_some() {
             self.word("const ");
         }
@@ -1249,7 +1359,11 @@ impl Printer {
         self.word("fn ");
         self.ident(&signature.ident);
         self.generics(&signature.generics);
-        self.word("(");
+        self.word(if self.config.punctuation.space_before_fn_paren {
+            " ("
+        } else {
+            "("
+        });
         self.neverbreak();
         self.cbox(0);
         self.zerobreak();
@@ -1265,9 +1379,11 @@ impl Printer {
         self.offset(-INDENT);
         self.end();
         self.word(")");
-        self.cbox(-INDENT);
-        self.return_type(&signature.output);
-        self.end();
+        if ty::returns_bound_list(&signature.output) {
+            // Break between the bounds of the return type before breaking the
+            // parameter list.
+            self.neverbreak();
+        }
     }
 
     fn fn_arg(&mut self, fn_arg: &FnArg) {
@@ -1279,6 +1395,26 @@ impl Printer {
 
     fn receiver(&mut self, receiver: &Receiver) {
         self.outer_attrs(&receiver.attrs);
+        // `self: Self`, `self: &Self` and `self: &'a mut Self` are written in
+        // their shorthand form, the same as if the source had used it.
+        let shorthand_ref = match (&receiver.reference, &receiver.mutability, &*receiver.ty) {
+            (None, None, Type::Reference(ty)) if is_self_type(&ty.elem) => {
+                Some((ty.lifetime.as_ref(), ty.mutability.is_some()))
+            }
+            _ => None,
+        };
+        if let Some((lifetime, mutability)) = shorthand_ref {
+            self.word("&");
+            if let Some(lifetime) = lifetime {
+                self.lifetime(lifetime);
+                self.nbsp();
+            }
+            if mutability {
+                self.word("mut ");
+            }
+            self.word("self");
+            return;
+        }
         if let Some((_ampersand, lifetime)) = &receiver.reference {
             self.word("&");
             if let Some(lifetime) = lifetime {
@@ -1290,32 +1426,25 @@ impl Printer {
             self.word("mut ");
         }
         self.word("self");
-        if receiver.colon_token.is_some() {
+        let consistent = match (&receiver.reference, &receiver.mutability, &*receiver.ty) {
+            (Some(_), mutability, Type::Reference(ty)) => {
+                receiver.colon_token.is_none()
+                    && mutability.is_some() == ty.mutability.is_some()
+                    && is_self_type(&ty.elem)
+            }
+            (None, _, ty) => is_self_type(ty),
+            _ => false,
+        };
+        if !consistent {
             self.word(": ");
             self.ty(&receiver.ty);
-        } else {
-            let consistent = match (&receiver.reference, &receiver.mutability, &*receiver.ty) {
-                (Some(_), mutability, Type::Reference(ty)) => {
-                    mutability.is_some() == ty.mutability.is_some()
-                        && match &*ty.elem {
-                            Type::Path(ty) => ty.qself.is_none() && ty.path.is_ident("Self"),
-                            _ => false,
-                        }
-                }
-                (None, _, Type::Path(ty)) => ty.qself.is_none() && ty.path.is_ident("Self"),
-                _ => false,
-            };
-            if !consistent {
-                self.word(": ");
-                self.ty(&receiver.ty);
-            }
         }
     }
 
     fn variadic(&mut self, variadic: &Variadic) {
         self.outer_attrs(&variadic.attrs);
         if let Some((pat, _colon)) = &variadic.pat {
-            self.pat(pat);
+            self.pat_no_top_alt(pat);
             self.word(": ");
         }
         self.word("...");
@@ -1329,6 +1458,45 @@ impl Printer {
             _ => unimplemented!("unknown StaticMutability"),
         }
     }
+
+    fn record_reordered<T>(
+        &mut self,
+        option: &'static str,
+        ordered: &[&T],
+        items: &[T],
+        owner: impl FnOnce() -> String,
+    ) {
+        let moved = ordered
+            .iter()
+            .zip(items)
+            .any(|(item, original)| !ptr::eq(*item, original));
+        if moved {
+            self.record(option, || format!("reordered the members of {}", owner()));
+        }
+    }
+
+    // The value of a const or static goes on the line after the `=` if it
+    // does not fit, unless it opens a delimiter of its own to break inside.
+    fn initializer_begin(&mut self, value: Option<&Expr>) {
+        if value.map_or(true, expr::opens_on_line) {
+            self.cbox(0);
+        } else {
+            self.ibox(self.config.continuation_indent);
+            self.ibox(-self.config.continuation_indent);
+        }
+    }
+
+    fn initializer(&mut self, value: &Expr) {
+        if expr::opens_on_line(value) {
+            self.word(" = ");
+            self.neverbreak();
+        } else {
+            self.word(" =");
+            self.end();
+            self.space();
+        }
+        self.expr(value);
+    }
 }
 
 #[cfg(feature = "verbatim")]
@@ -1545,7 +1713,7 @@ mod verbatim {
         }
     }
 
-    impl Printer {
+    impl Printer<'_> {
         pub fn flexible_item_const(&mut self, item: &FlexibleItemConst) {
             self.outer_attrs(&item.attrs);
             self.cbox(0);
@@ -1569,19 +1737,19 @@ mod verbatim {
             if item.defaultness {
                 self.word("default ");
             }
-            self.signature(&item.sig);
             if let Some(body) = &item.body {
-                self.where_clause_for_body(&item.sig.generics.where_clause);
+                self.signature_for_body(&item.sig);
                 self.word("{");
                 self.hardbreak_if_nonempty();
                 self.inner_attrs(&item.attrs);
-                for stmt in body {
-                    self.stmt(stmt);
+                for stmt in body.iter().delimited() {
+                    self.stmt(&stmt, stmt.is_last);
                 }
                 self.offset(-INDENT);
                 self.end();
                 self.word("}");
             } else {
+                self.signature(&item.sig);
                 self.where_clause_semi(&item.sig.generics.where_clause);
                 self.end();
             }
@@ -1590,7 +1758,7 @@ mod verbatim {
 
         pub fn flexible_item_static(&mut self, item: &FlexibleItemStatic) {
             self.outer_attrs(&item.attrs);
-            self.cbox(0);
+            self.initializer_begin(item.expr.as_ref());
             self.visibility(&item.vis);
             self.word("static ");
             self.static_mutability(&item.mutability);
@@ -1600,9 +1768,7 @@ mod verbatim {
                 self.ty(ty);
             }
             if let Some(expr) = &item.expr {
-                self.word(" = ");
-                self.neverbreak();
-                self.expr(expr);
+                self.initializer(expr);
             }
             self.word(";");
             self.end();
@@ -1644,3 +1810,111 @@ mod verbatim {
         }
     }
 }
+
+// A group of one import, other than `self`: `a::{self}` is not the same
+// import as `a`, and `a::self` is not valid.
+fn is_collapsible(use_group: &UseGroup) -> bool {
+    if use_group.items.len() != 1 {
+        return false;
+    }
+    match &use_group.items[0] {
+        UseTree::Name(use_name) => use_name.ident != "self",
+        UseTree::Rename(use_rename) => use_rename.ident != "self",
+        UseTree::Path(_) | UseTree::Glob(_) | UseTree::Group(_) => true,
+    }
+}
+
+fn is_self_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ty) => ty.qself.is_none() && ty.path.is_ident("Self"),
+        _ => false,
+    }
+}
+
+fn impl_items(items: &[ImplItem], reorder: bool) -> Vec<&ImplItem> {
+    members_in_order(items, reorder, |item| match item {
+        ImplItem::Const(item) => member_rank(&item.attrs, 0),
+        ImplItem::Type(item) => member_rank(&item.attrs, 1),
+        ImplItem::Fn(item) => member_rank(&item.attrs, 2),
+        _ => None,
+    })
+}
+
+fn trait_items(items: &[TraitItem], reorder: bool) -> Vec<&TraitItem> {
+    members_in_order(items, reorder, |item| match item {
+        TraitItem::Const(item) => member_rank(&item.attrs, 0),
+        TraitItem::Type(item) => member_rank(&item.attrs, 1),
+        TraitItem::Fn(item) => member_rank(&item.attrs, 2),
+        _ => None,
+    })
+}
+
+// Modules are ordered as extern crates, then imports, then named items
+// alphabetically, then impls and foreign blocks. Imports keep their relative
+// order. Macro invocations are fixed points, as they may define or rely on
+// macros by textual order, and so are re-exports unless `reorder_pub_use`.
+fn module_items(items: &[Item], reorder: bool, reorder_pub_use: bool) -> Vec<&Item> {
+    members_in_order(items, reorder, |item| {
+        let (attrs, rank, name) = match item {
+            Item::ExternCrate(item) => (&item.attrs, 0, None),
+            Item::Use(item) => {
+                if !reorder_pub_use && !matches!(item.vis, Visibility::Inherited) {
+                    return None;
+                }
+                (&item.attrs, 1, None)
+            }
+            Item::Const(item) => (&item.attrs, 2, Some(&item.ident)),
+            Item::Enum(item) => (&item.attrs, 2, Some(&item.ident)),
+            Item::Fn(item) => (&item.attrs, 2, Some(&item.sig.ident)),
+            Item::Mod(item) => (&item.attrs, 2, Some(&item.ident)),
+            Item::Static(item) => (&item.attrs, 2, Some(&item.ident)),
+            Item::Struct(item) => (&item.attrs, 2, Some(&item.ident)),
+            Item::Trait(item) => (&item.attrs, 2, Some(&item.ident)),
+            Item::TraitAlias(item) => (&item.attrs, 2, Some(&item.ident)),
+            Item::Type(item) => (&item.attrs, 2, Some(&item.ident)),
+            Item::Union(item) => (&item.attrs, 2, Some(&item.ident)),
+            Item::ForeignMod(item) => (&item.attrs, 3, None),
+            Item::Impl(item) => (&item.attrs, 3, None),
+            // Macros defined by `macro_rules!` are in scope only after their
+            // definition, so neither the definition nor anything else that
+            // could invoke it may move past it.
+            Item::Macro(_) => return None,
+            _ => return None,
+        };
+        // Same for the macros brought in by a `#[macro_use]` crate or module.
+        if attrs.iter().any(|attr| attr.path().is_ident("macro_use")) {
+            return None;
+        }
+        let name = name.map_or_else(String::new, Ident::to_string);
+        member_rank(attrs, (rank, name))
+    })
+}
+
+// A conditionally compiled member is a fixed point, as it may be standing in
+// for a member that is defined differently in another configuration.
+fn member_rank<K>(attrs: &[Attribute], rank: K) -> Option<K> {
+    let is_cfg = attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("cfg") || attr.path().is_ident("cfg_attr"));
+    if is_cfg {
+        None
+    } else {
+        Some(rank)
+    }
+}
+
+// Stably sorts each run of members between fixed points, which are the members
+// that `rank` returns None for.
+fn members_in_order<T, K: Ord>(
+    items: &[T],
+    reorder: bool,
+    rank: impl Fn(&T) -> Option<K>,
+) -> Vec<&T> {
+    let mut items: Vec<&T> = items.iter().collect();
+    if reorder {
+        for run in items.split_mut(|item| rank(item).is_none()) {
+            run.sort_by_cached_key(|item| rank(item));
+        }
+    }
+    items
+}
@@ -343,8 +343,10 @@
 
 mod algorithm;
 mod attr;
+mod config;
 mod convenience;
 mod data;
+mod error;
 mod expr;
 mod file;
 mod generics;
@@ -355,13 +357,24 @@ mod lit;
 mod mac;
 mod pat;
 mod path;
+mod precedence;
+mod report;
 mod ring;
+mod sink;
 mod stmt;
 mod token;
 mod ty;
 
 use crate::algorithm::Printer;
-use syn::File;
+use syn::{File, Signature};
+
+pub use crate::config::{
+    AbsolutePathStyle, Case, Config, DocStyle, Edition, EmptyBlock, Punctuation, QuoteStyle,
+    RangeSpacing, WidthMetric,
+};
+pub use crate::error::Error;
+pub use crate::report::TransformationEvent;
+pub use crate::sink::{Sink, WriteSink};
 
 // Target line width.
 const MARGIN: isize = 89;
@@ -372,8 +385,203 @@ const INDENT: isize = 4;
 // Every line is allowed at least this much space, even if highly indented.
 const MIN_SPACE: isize = 60;
 
+// Deepest nesting of expressions, patterns and types that will be printed.
+// Comfortably fits in a 2 MiB thread stack even in an unoptimized build.
+const MAX_DEPTH: usize = 1000;
+
+/// Formats a syntax tree as Rust source code.
+///
+/// An expression, pattern or type nested more than 1000 levels deep is printed
+/// as a `compile_error!` macro call in place of its innermost levels, rather
+/// than risking a stack overflow. Use [`unparse_to_sink`] or
+/// [`try_unparse_checked`] to find out when that happened. The same goes for
+/// every other `unparse` function.
 pub fn unparse(file: &File) -> String {
-    let mut p = Printer::new();
+    unparse_with_config(file, &Config::default())
+}
+
+/// Like [`unparse`], but takes the file by value, for formatting a `File`
+/// that was just built or parsed without binding it to a variable first.
+pub fn unparse_owned(file: File) -> String {
+    unparse(&file)
+}
+
+/// Like [`unparse`], but with the layout adjusted according to `config`.
+pub fn unparse_with_config(file: &File, config: &Config) -> String {
+    let mut out = String::new();
+    // Too deep nesting is left in the output as a compile_error!.
+    let _ = unparse_to_sink(file, config, &mut out);
+    out
+}
+
+/// Like [`unparse_with_config`], but writes the output into `sink` as it is
+/// produced instead of collecting it into a `String`.
+///
+/// Returns an error if some part of the syntax tree was nested too deeply to
+/// be printed. The output written to `sink` is complete regardless, with a
+/// `compile_error!` in place of the part that was too deep.
+pub fn unparse_to_sink(file: &File, config: &Config, sink: &mut dyn Sink) -> Result<(), Error> {
+    let mut p = Printer::new(config.clone(), sink);
     p.file(file);
-    p.eof()
+    let too_deep = p.is_too_deep();
+    p.eof();
+    if too_deep {
+        return Err(Error::too_deep());
+    }
+    Ok(())
+}
+
+/// Like [`unparse_with_config`], but also returns the changes made to the code
+/// by the rewriting options of `config`, such as reordering fields or
+/// rewriting string literals, in the order they were made.
+///
+/// The list is empty unless
+/// [`Config::record_transformations`][Config::record_transformations] is set.
+pub fn unparse_with_report(file: &File, config: &Config) -> (String, Vec<TransformationEvent>) {
+    let mut out = String::new();
+    let mut p = Printer::new(config.clone(), &mut out);
+    p.file(file);
+    let events = p.take_events();
+    p.eof();
+    (out, events)
+}
+
+/// Formats each file of `files` in turn as the returned iterator is advanced.
+///
+/// Each output buffer is preallocated at the size of the previous file's
+/// output, which avoids most reallocation when formatting many similar files.
+pub fn unparse_files<'a, I>(files: I) -> impl Iterator<Item = String> + 'a
+where
+    I: IntoIterator<Item = &'a File>,
+    I::IntoIter: 'a,
+{
+    let config = Config::default();
+    let mut capacity = 0;
+    files.into_iter().map(move |file| {
+        let mut out = String::with_capacity(capacity);
+        let _ = unparse_to_sink(file, &config, &mut out);
+        capacity = out.len();
+        out
+    })
+}
+
+/// Like [`unparse`], but annotated with the printer's box and break markers.
+/// See [`Config::debug_markers`].
+pub fn unparse_debug(file: &File) -> String {
+    unparse_with_config(file, &Config::new().debug_markers(true))
+}
+
+/// Like [`unparse`], but also reports whether the output differs from
+/// `original`, the source text that `file` was parsed from.
+pub fn unparse_checked(file: &File, original: &str) -> (String, bool) {
+    let formatted = unparse(file);
+    let changed = formatted != original;
+    (formatted, changed)
+}
+
+/// Like [`unparse_checked`], but expressions, patterns and types nested more
+/// than 1000 levels deep are an error, where the other `unparse` functions
+/// print a `compile_error!` in their place.
+pub fn try_unparse_checked(file: &File, original: &str) -> Result<(String, bool), Error> {
+    let mut formatted = String::new();
+    unparse_to_sink(file, &Config::default(), &mut formatted)?;
+    let changed = formatted != original;
+    Ok((formatted, changed))
+}
+
+/// Formats a function signature on its own: qualifiers, name, generics,
+/// parameters, return type and where-clause, without a body or a trailing
+/// semicolon. Long signatures break the same way as in a full `fn` item.
+pub fn unparse_signature(sig: &Signature) -> String {
+    let mut out = String::new();
+    let mut p = Printer::new(Config::default(), &mut out);
+    p.standalone_signature(sig);
+    p.eof();
+    // A where-clause ends with the line break before the body's `{`.
+    out.truncate(out.trim_end().len());
+    out
+}
+
+/// Width of each line of `output`, not counting the line terminator.
+///
+/// Width is measured by `metric`, the same way the printer measures text
+/// against the margin when configured with [`Config::width_metric`]. Pass the
+/// metric `output` was formatted with to check it against the margin.
+pub fn line_widths(output: &str, metric: WidthMetric) -> Vec<usize> {
+    output
+        .lines()
+        .map(|line| algorithm::width(metric, line))
+        .collect()
+}
+
+/// Width of the widest line of `output`, measured as in [`line_widths`].
+pub fn max_line_width(output: &str, metric: WidthMetric) -> usize {
+    output
+        .lines()
+        .map(|line| algorithm::width(metric, line))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Parses `src` as a file and reports whether it is already formatted exactly
+/// the way [`unparse`] would format it.
+#[cfg(feature = "verbatim")]
+pub fn is_formatted(src: &str) -> syn::Result<bool> {
+    let file = syn::parse_file(src)?;
+    Ok(unparse(&file) == src)
+}
+
+/// Formats `src`, which may be a whole file or just a fragment of one: a
+/// sequence of statements, an expression, a type or a pattern. The first of
+/// those that `src` parses as, in that order, is what it is formatted as.
+///
+/// If `src` does not parse as any of them, the error from parsing it as a file
+/// is returned.
+#[cfg(feature = "verbatim")]
+pub fn format_fragment(src: &str) -> syn::Result<String> {
+    use crate::iter::IterDelimited;
+    use syn::parse::Parser;
+    use syn::{Block, Expr, Pat, Type};
+
+    let err = match syn::parse_file(src) {
+        Ok(file) => return Ok(unparse(&file)),
+        Err(err) => err,
+    };
+    if let Ok(stmts) = Block::parse_within.parse_str(src) {
+        return Ok(unparse_fragment(|p| {
+            for stmt in stmts.iter().delimited() {
+                p.stmt(&stmt, stmt.is_last);
+            }
+        }));
+    }
+    if let Ok(expr) = syn::parse_str::<Expr>(src) {
+        return Ok(unparse_fragment(|p| {
+            p.expr(&expr);
+            p.hardbreak();
+        }));
+    }
+    if let Ok(ty) = syn::parse_str::<Type>(src) {
+        return Ok(unparse_fragment(|p| {
+            p.ty(&ty);
+            p.hardbreak();
+        }));
+    }
+    if let Ok(pat) = Pat::parse_multi_with_leading_vert.parse_str(src) {
+        return Ok(unparse_fragment(|p| {
+            p.pat(&pat);
+            p.hardbreak();
+        }));
+    }
+    Err(err)
+}
+
+#[cfg(feature = "verbatim")]
+fn unparse_fragment(print: impl FnOnce(&mut Printer)) -> String {
+    let mut out = String::new();
+    let mut p = Printer::new(Config::default(), &mut out);
+    p.cbox(0);
+    print(&mut p);
+    p.end();
+    p.eof();
+    out
 }
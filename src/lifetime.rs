@@ -1,7 +1,7 @@
 use crate::algorithm::Printer;
 use syn::Lifetime;
 
-impl Printer {
+impl Printer<'_> {
     pub fn lifetime(&mut self, lifetime: &Lifetime) {
         self.word("'");
         self.ident(&lifetime.ident);
@@ -1,8 +1,9 @@
 use crate::algorithm::Printer;
+use crate::config::{Case, QuoteStyle};
 use proc_macro2::Literal;
 use syn::{Lit, LitBool, LitByte, LitByteStr, LitChar, LitFloat, LitInt, LitStr};
 
-impl Printer {
+impl Printer<'_> {
     pub fn lit(&mut self, lit: &Lit) {
         match lit {
             Lit::Str(lit) => self.lit_str(lit),
@@ -19,7 +20,31 @@ impl Printer {
     }
 
     pub fn lit_str(&mut self, lit: &LitStr) {
-        self.word(lit.token().to_string());
+        let repr = lit.token().to_string();
+        let is_raw = repr.starts_with('r');
+        if !lit.suffix().is_empty() {
+            self.word(repr);
+            return;
+        }
+        match self.config.quote_style {
+            QuoteStyle::PreferRaw if !is_raw => {
+                let value = lit.value();
+                if value.contains(['\\', '"']) && !value.contains(char::is_control) {
+                    let raw = raw_str(&value);
+                    self.record("quote_style", || format!("{} -> {}", repr, raw));
+                    self.word(raw);
+                    return;
+                }
+            }
+            QuoteStyle::PreferEscaped if is_raw => {
+                let escaped = escaped_str(&lit.value());
+                self.record("quote_style", || format!("{} -> {}", repr, escaped));
+                self.word(escaped);
+                return;
+            }
+            _ => {}
+        }
+        self.word(repr);
     }
 
     fn lit_byte_str(&mut self, lit: &LitByteStr) {
@@ -35,11 +60,35 @@ impl Printer {
     }
 
     fn lit_int(&mut self, lit: &LitInt) {
-        self.word(lit.token().to_string());
+        let mut repr = lit.token().to_string();
+        if let Some(case) = self.config.hex_case {
+            if repr.starts_with("0x") {
+                let end = repr.len() - lit.suffix().len();
+                let digits = set_case(&repr[2..end], case);
+                if digits != repr[2..end] {
+                    let original = repr.clone();
+                    repr.replace_range(2..end, &digits);
+                    self.record("hex_case", || format!("{} -> {}", original, repr));
+                }
+            }
+        }
+        self.word(repr);
     }
 
     fn lit_float(&mut self, lit: &LitFloat) {
-        self.word(lit.token().to_string());
+        let mut repr = lit.token().to_string();
+        if let Some(case) = self.config.exponent_case {
+            // Float literals are always decimal, so the only letter before
+            // the suffix is the exponent marker.
+            let end = repr.len() - lit.suffix().len();
+            let digits = set_case(&repr[..end], case);
+            if digits != repr[..end] {
+                let original = repr.clone();
+                repr.replace_range(..end, &digits);
+                self.record("exponent_case", || format!("{} -> {}", original, repr));
+            }
+        }
+        self.word(repr);
     }
 
     fn lit_bool(&mut self, lit: &LitBool) {
@@ -50,3 +99,35 @@ impl Printer {
         self.word(token.to_string());
     }
 }
+
+fn raw_str(value: &str) -> String {
+    // Enough `#` that no quote in the value followed by `#`s can be mistaken
+    // for the end of the literal.
+    let mut hashes = 0;
+    for (i, _) in value.match_indices('"') {
+        let run = value[i + 1..].bytes().take_while(|&b| b == b'#').count();
+        hashes = hashes.max(run + 1);
+    }
+    let hashes = "#".repeat(hashes);
+    format!("r{}\"{}\"{}", hashes, value, hashes)
+}
+
+fn escaped_str(value: &str) -> String {
+    let mut repr = String::with_capacity(value.len() + 2);
+    repr.push('"');
+    for ch in value.chars() {
+        if let '\\' | '"' = ch {
+            repr.push('\\');
+        }
+        repr.push(ch);
+    }
+    repr.push('"');
+    repr
+}
+
+fn set_case(repr: &str, case: Case) -> String {
+    match case {
+        Case::Lower => repr.to_ascii_lowercase(),
+        Case::Upper => repr.to_ascii_uppercase(),
+    }
+}
@@ -1,17 +1,21 @@
 use crate::algorithm::Printer;
+use crate::iter::IterDelimited;
 use crate::path::PathKind;
 use crate::token::Token;
 use crate::INDENT;
-use proc_macro2::{Delimiter, Spacing, TokenStream};
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use std::iter;
 use syn::{Ident, Macro, MacroDelimiter};
 
-impl Printer {
+impl Printer<'_> {
     pub fn mac(&mut self, mac: &Macro, ident: Option<&Ident>) {
         if mac.path.is_ident("macro_rules") {
             if let Some(ident) = ident {
                 self.macro_rules(ident, &mac.tokens);
                 return;
             }
+        } else if ident.is_none() && self.vec_macro(mac) {
+            return;
         }
         self.path(&mac.path, PathKind::Simple);
         self.word("!");
@@ -25,7 +29,22 @@ impl Printer {
             MacroDelimiter::Bracket(_) => ("[", "]", Self::zerobreak as fn(&mut Self)),
         };
         self.word(open);
-        if !mac.tokens.is_empty() {
+        if let Some(format) = self.config.macro_formatter(&mac.path) {
+            let body = format(&mac.tokens);
+            if !body.is_empty() {
+                self.cbox(INDENT);
+                delimiter_break(self);
+                for (i, line) in body.lines().enumerate() {
+                    if i > 0 {
+                        self.hardbreak();
+                    }
+                    self.word(line.to_owned());
+                }
+                delimiter_break(self);
+                self.offset(-INDENT);
+                self.end();
+            }
+        } else if !mac.tokens.is_empty() {
             self.cbox(INDENT);
             delimiter_break(self);
             self.ibox(0);
@@ -38,6 +57,35 @@ impl Printer {
         self.word(close);
     }
 
+    #[cfg(not(feature = "verbatim"))]
+    fn vec_macro(&mut self, _mac: &Macro) -> bool {
+        false
+    }
+
+    // The contents of `vec![...]` are laid out like an array expression, one
+    // element per line if they do not fit on one line, when they parse as one.
+    #[cfg(feature = "verbatim")]
+    fn vec_macro(&mut self, mac: &Macro) -> bool {
+        use proc_macro2::{Group, TokenTree};
+        use syn::Expr;
+
+        if !mac.path.is_ident("vec")
+            || !matches!(mac.delimiter, MacroDelimiter::Bracket(_))
+            || !self.config.format_macro_bodies
+            || self.config.macro_formatter(&mac.path).is_some()
+        {
+            return false;
+        }
+        let group = Group::new(Delimiter::Bracket, mac.tokens.clone());
+        let expr: Expr = match syn::parse2(TokenStream::from(TokenTree::Group(group))) {
+            Ok(expr @ (Expr::Array(_) | Expr::Repeat(_))) => expr,
+            _ => return false,
+        };
+        self.word("vec!");
+        self.expr(&expr);
+        true
+    }
+
     pub fn mac_semi_if_needed(&mut self, delimiter: &MacroDelimiter) {
         match delimiter {
             MacroDelimiter::Paren(_) | MacroDelimiter::Bracket(_) => self.word(";"),
@@ -67,7 +115,9 @@ impl Printer {
             match (state, token) {
                 (Start, Token::Group(delimiter, stream)) => {
                     self.delimiter_open(delimiter);
-                    if !stream.is_empty() {
+                    if !stream.is_empty() && self.config.format_macro_matchers {
+                        self.macro_matcher_fragments(stream);
+                    } else if !stream.is_empty() {
                         self.cbox(INDENT);
                         self.zerobreak();
                         self.ibox(0);
@@ -125,6 +175,56 @@ impl Printer {
         self.word("}");
     }
 
+    // A matcher split at its top-level commas, other than the separator of a
+    // `$(...),*` repetition, with either all of the fragments on one line or
+    // each on its own line.
+    fn macro_matcher_fragments(&mut self, stream: TokenStream) {
+        let mut fragments = vec![TokenStream::new()];
+        let mut trailing_comma = false;
+        let mut previous = None;
+        let mut repetition = false;
+        for tt in stream {
+            let is_repetition = match (&previous, &tt) {
+                (Some(TokenTree::Punct(punct)), TokenTree::Group(group)) => {
+                    punct.as_char() == '$' && group.delimiter() == Delimiter::Parenthesis
+                }
+                _ => false,
+            };
+            match &tt {
+                TokenTree::Punct(punct) if punct.as_char() == ',' && !repetition => {
+                    fragments.push(TokenStream::new());
+                    trailing_comma = true;
+                }
+                _ => {
+                    fragments.last_mut().unwrap().extend(iter::once(tt.clone()));
+                    trailing_comma = false;
+                }
+            }
+            repetition = is_repetition;
+            previous = Some(tt);
+        }
+        if trailing_comma {
+            fragments.pop();
+        }
+
+        self.cbox(INDENT);
+        self.zerobreak();
+        for fragment in fragments.into_iter().delimited() {
+            self.ibox(0);
+            self.macro_rules_tokens(fragment.clone(), true);
+            self.end();
+            if !fragment.is_last {
+                self.word(",");
+                self.space();
+            } else if trailing_comma {
+                self.word(",");
+            }
+        }
+        self.zerobreak();
+        self.offset(-INDENT);
+        self.end();
+    }
+
     pub fn macro_rules_tokens(&mut self, stream: TokenStream, matcher: bool) {
         #[derive(PartialEq)]
         enum State {
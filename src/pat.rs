@@ -4,12 +4,15 @@ use crate::path::PathKind;
 use crate::INDENT;
 use proc_macro2::TokenStream;
 use syn::{
-    FieldPat, Pat, PatIdent, PatOr, PatParen, PatReference, PatRest, PatSlice, PatStruct, PatTuple,
-    PatTupleStruct, PatType, PatWild,
+    FieldPat, Member, Pat, PatIdent, PatOr, PatParen, PatReference, PatRest, PatSlice, PatStruct,
+    PatTuple, PatTupleStruct, PatType, PatWild,
 };
 
-impl Printer {
+impl Printer<'_> {
     pub fn pat(&mut self, pat: &Pat) {
+        if !self.enter_nested() {
+            return;
+        }
         match pat {
             Pat::Const(pat) => self.expr_const(pat),
             Pat::Ident(pat) => self.pat_ident(pat),
@@ -31,6 +34,7 @@ impl Printer {
             #[cfg_attr(all(test, exhaustive), deny(non_exhaustive_omitted_patterns))]
             _ => unimplemented!("unknown Pat"),
         }
+        self.leave_nested();
     }
 
     fn pat_ident(&mut self, pat: &PatIdent) {
@@ -75,6 +79,20 @@ impl Printer {
         self.end();
     }
 
+    // ERROR CORRECTION: An or-pattern at the top level of a `let`, of a
+    // function or closure parameter, or in front of a type annotation is only
+    // parsed inside parentheses. Add them if the syntax tree does not have
+    // them.
+    pub fn pat_no_top_alt(&mut self, pat: &Pat) {
+        if let Pat::Or(_) = pat {
+            self.word("(");
+            self.pat(pat);
+            self.word(")");
+        } else {
+            self.pat(pat);
+        }
+    }
+
     fn pat_paren(&mut self, pat: &PatParen) {
         self.outer_attrs(&pat.attrs);
         self.word("(");
@@ -101,7 +119,11 @@ impl Printer {
         self.word("[");
         for elem in pat.elems.iter().delimited() {
             self.pat(&elem);
-            self.trailing_comma(elem.is_last);
+            if elem.is_last && is_rest(&elem) {
+                self.zerobreak();
+            } else {
+                self.trailing_comma(elem.is_last);
+            }
         }
         self.word("]");
     }
@@ -137,6 +159,8 @@ impl Printer {
                     self.word(",");
                 }
                 self.zerobreak();
+            } else if elem.is_last && is_rest(&elem) {
+                self.zerobreak();
             } else {
                 self.trailing_comma(elem.is_last);
             }
@@ -154,7 +178,11 @@ impl Printer {
         self.zerobreak();
         for elem in pat.elems.iter().delimited() {
             self.pat(&elem);
-            self.trailing_comma(elem.is_last);
+            if elem.is_last && is_rest(&elem) {
+                self.zerobreak();
+            } else {
+                self.trailing_comma(elem.is_last);
+            }
         }
         self.offset(-INDENT);
         self.end();
@@ -163,7 +191,7 @@ impl Printer {
 
     pub fn pat_type(&mut self, pat: &PatType) {
         self.outer_attrs(&pat.attrs);
-        self.pat(&pat.pat);
+        self.pat_no_top_alt(&pat.pat);
         self.word(": ");
         self.ty(&pat.ty);
     }
@@ -237,10 +265,27 @@ impl Printer {
 
     fn field_pat(&mut self, field_pat: &FieldPat) {
         self.outer_attrs(&field_pat.attrs);
-        if field_pat.colon_token.is_some() {
+        // `x: x` is written as the shorthand `x`, regardless of how it was
+        // written originally.
+        let shorthand = match (&field_pat.member, &*field_pat.pat) {
+            (Member::Named(member), Pat::Ident(pat)) => {
+                pat.ident == *member && pat.subpat.is_none() && pat.attrs.is_empty()
+            }
+            _ => false,
+        };
+        if !shorthand {
             self.member(&field_pat.member);
             self.word(": ");
         }
         self.pat(&field_pat.pat);
     }
 }
+
+// A trailing `..` is not followed by a comma, even when broken onto its own
+// line, same as in struct patterns.
+fn is_rest(pat: &Pat) -> bool {
+    match pat {
+        Pat::Rest(_) => true,
+        _ => false,
+    }
+}
@@ -1,6 +1,8 @@
 use crate::algorithm::Printer;
+use crate::config::{AbsolutePathStyle, Edition};
 use crate::iter::IterDelimited;
 use crate::INDENT;
+use proc_macro2::Ident;
 use std::ptr;
 use syn::{
     AngleBracketedGenericArguments, AssocConst, AssocType, Constraint, Expr, GenericArgument,
@@ -17,17 +19,46 @@ pub enum PathKind {
     Expr,
 }
 
-impl Printer {
+impl Printer<'_> {
     pub fn path(&mut self, path: &Path, kind: PathKind) {
         assert!(!path.segments.is_empty());
+        let leading_colon = self.path_leading_colon(path);
         for segment in path.segments.iter().delimited() {
-            if !segment.is_first || path.leading_colon.is_some() {
+            if !segment.is_first || leading_colon {
                 self.word("::");
             }
             self.path_segment(&segment, kind);
         }
     }
 
+    pub fn path_leading_colon(&mut self, path: &Path) -> bool {
+        self.leading_colon(
+            path.leading_colon.is_some(),
+            &path.segments[0].ident,
+            path.segments.len() > 1,
+        )
+    }
+
+    // Whether a path starting with `first` is printed with a leading `::`,
+    // given whether it was written with one and whether more segments follow.
+    pub fn leading_colon(&mut self, leading_colon: bool, first: &Ident, has_rest: bool) -> bool {
+        if !has_rest || !is_std_crate(first) {
+            return leading_colon;
+        }
+        let result = match self.config.absolute_paths {
+            AbsolutePathStyle::Preserve => leading_colon,
+            AbsolutePathStyle::Global => true,
+            AbsolutePathStyle::Relative => leading_colon && self.config.edition < Edition::E2018,
+        };
+        if result != leading_colon {
+            self.record("absolute_paths", || {
+                let action = if result { "added" } else { "removed" };
+                format!("{} leading `::` of path into `{}`", action, first)
+            });
+        }
+        result
+    }
+
     pub fn path_segment(&mut self, segment: &PathSegment, kind: PathKind) {
         self.ident(&segment.ident);
         self.path_arguments(&segment.arguments, kind);
@@ -84,7 +115,10 @@ impl Printer {
         }
         self.word("<");
         self.cbox(INDENT);
-        self.zerobreak();
+        if self.config.generics_trailing_comma_heuristic && generic.args.trailing_punct() {
+            self.force_break();
+        }
+        self.angle_bracket_open_break();
 
         // Print lifetimes before types/consts/bindings, regardless of their
         // order in self.args.
@@ -110,7 +144,7 @@ impl Printer {
             for arg in &generic.args {
                 if group(arg) == current_group {
                     self.generic_argument(arg);
-                    self.trailing_comma(ptr::eq(arg, last.unwrap()));
+                    self.angle_bracket_separator(ptr::eq(arg, last.unwrap()));
                 }
             }
         }
@@ -187,8 +221,9 @@ impl Printer {
         let mut segments = path.segments.iter();
         if qself.position > 0 {
             self.word(" as ");
+            let leading_colon = self.path_leading_colon(path);
             for segment in segments.by_ref().take(qself.position).delimited() {
-                if !segment.is_first || path.leading_colon.is_some() {
+                if !segment.is_first || leading_colon {
                     self.word("::");
                 }
                 self.path_segment(&segment, PathKind::Type);
@@ -205,3 +240,7 @@ impl Printer {
         }
     }
 }
+
+fn is_std_crate(ident: &Ident) -> bool {
+    ident == "std" || ident == "core" || ident == "alloc"
+}
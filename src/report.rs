@@ -0,0 +1,13 @@
+/// A change to the code, as opposed to its layout, made by one of the
+/// rewriting options of [`Config`][crate::Config]. Collected by
+/// [`unparse_with_report`][crate::unparse_with_report] when
+/// [`Config::record_transformations`][crate::Config::record_transformations]
+/// is enabled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransformationEvent {
+    /// Name of the `Config` method that enabled the change, like
+    /// `"quote_style"`.
+    pub option: &'static str,
+    /// Human-readable description of the change.
+    pub description: String,
+}
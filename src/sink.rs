@@ -0,0 +1,58 @@
+use std::io;
+
+/// Destination that formatted code is written into, piece by piece, as the
+/// printer produces it.
+pub trait Sink {
+    fn push_str(&mut self, string: &str);
+
+    fn push_char(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]));
+    }
+}
+
+impl Sink for String {
+    fn push_str(&mut self, string: &str) {
+        String::push_str(self, string);
+    }
+
+    fn push_char(&mut self, ch: char) {
+        self.push(ch);
+    }
+}
+
+/// [`Sink`] that forwards output to an [`io::Write`].
+///
+/// Writing stops at the first I/O error, which is reported by
+/// [`finish`][WriteSink::finish].
+pub struct WriteSink<W> {
+    writer: W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> WriteSink<W> {
+    pub fn new(writer: W) -> Self {
+        WriteSink {
+            writer,
+            error: None,
+        }
+    }
+
+    /// Returns the underlying writer, or the first error that writing to it
+    /// produced.
+    pub fn finish(self) -> io::Result<W> {
+        match self.error {
+            None => Ok(self.writer),
+            Some(error) => Err(error),
+        }
+    }
+}
+
+impl<W: io::Write> Sink for WriteSink<W> {
+    fn push_str(&mut self, string: &str) {
+        if self.error.is_none() {
+            if let Err(error) = self.writer.write_all(string.as_bytes()) {
+                self.error = Some(error);
+            }
+        }
+    }
+}
@@ -1,15 +1,24 @@
 use crate::algorithm::Printer;
+use crate::attr;
+use crate::expr::binary_operand_parens;
 use crate::INDENT;
-use syn::{BinOp, Expr, Stmt};
+use syn::{
+    Attribute, BinOp, Block, Expr, ExprArray, ExprAssign, ExprAsync, ExprAwait, ExprBinary,
+    ExprBlock, ExprBreak, ExprCall, ExprCast, ExprClosure, ExprConst, ExprContinue, ExprField,
+    ExprForLoop, ExprGroup, ExprIf, ExprIndex, ExprInfer, ExprLet, ExprLit, ExprLoop, ExprMacro,
+    ExprMatch, ExprMethodCall, ExprParen, ExprPath, ExprRange, ExprReference, ExprRepeat,
+    ExprReturn, ExprStruct, ExprTry, ExprTryBlock, ExprTuple, ExprUnary, ExprUnsafe, ExprWhile,
+    ExprYield, MacroDelimiter, Stmt,
+};
 
-impl Printer {
-    pub fn stmt(&mut self, stmt: &Stmt) {
+impl Printer<'_> {
+    pub fn stmt(&mut self, stmt: &Stmt, is_last: bool) {
         match stmt {
             Stmt::Local(local) => {
                 self.outer_attrs(&local.attrs);
                 self.ibox(0);
                 self.word("let ");
-                self.pat(&local.pat);
+                self.pat_no_top_alt(&local.pat);
                 if let Some(local_init) = &local.init {
                     self.word(" = ");
                     self.neverbreak();
@@ -37,26 +46,35 @@ impl Printer {
             Stmt::Item(item) => self.item(item),
             Stmt::Expr(expr, None) => {
                 if break_after(expr) {
-                    self.ibox(0);
-                    self.expr_beginning_of_line(expr, true);
+                    self.stmt_expr_begin(expr);
+                    self.stmt_expr(expr);
                     if add_semi(expr) {
                         self.word(";");
                     }
                     self.end();
                     self.hardbreak();
                 } else {
-                    self.expr_beginning_of_line(expr, true);
+                    self.stmt_expr(expr);
                 }
             }
             Stmt::Expr(expr, Some(_semi)) => {
                 if let Expr::Verbatim(tokens) = expr {
+                    // A stray `;` on its own is an empty statement.
                     if tokens.is_empty() {
+                        if self.config.strip_empty_statements {
+                            self.record("strip_empty_statements", || {
+                                "removed empty statement `;`".to_owned()
+                            });
+                        } else {
+                            self.word(";");
+                            self.hardbreak();
+                        }
                         return;
                     }
                 }
-                self.ibox(0);
-                self.expr_beginning_of_line(expr, true);
-                if !remove_semi(expr) {
+                self.stmt_expr_begin(expr);
+                self.stmt_expr(expr);
+                if !remove_semi(expr, is_last) {
                     self.word(";");
                 }
                 self.end();
@@ -65,11 +83,51 @@ impl Printer {
             Stmt::Macro(stmt) => {
                 self.outer_attrs(&stmt.attrs);
                 self.mac(&stmt.mac, None);
-                self.mac_semi_if_needed(&stmt.mac.delimiter);
+                // Without a semicolon, a last macro is the block's trailing
+                // expression and needs to stay that way. Anywhere else, only a
+                // braced macro can go without one.
+                if is_last {
+                    if stmt.semi_token.is_some() {
+                        self.word(";");
+                    }
+                } else {
+                    self.mac_semi_if_needed(&stmt.mac.delimiter);
+                }
                 self.hardbreak();
             }
         }
     }
+
+    // Outer attributes on an expression statement always go on their own
+    // line, same as on any other statement, even if the whole statement would
+    // fit on one line. They are printed ahead of the boxes of the expression,
+    // whose leftmost operand they belong to in `#[cfg(a)] x += 1;`.
+    fn stmt_expr_begin(&mut self, expr: &Expr) {
+        let attrs = leading_attrs(expr);
+        if attr::has_outer(attrs) && !leftmost_ends_stmt(expr, false) {
+            self.cbox(0);
+            self.force_break();
+            self.outer_attrs(attrs);
+            self.end();
+            self.set_leading_attrs_printed(true);
+        }
+        self.ibox(0);
+    }
+
+    fn stmt_expr(&mut self, expr: &Expr) {
+        // ERROR CORRECTION: In `{ a } - 1;` or `match x {} as T;` the parser
+        // would end the statement at the closing brace. Parenthesize the
+        // whole statement so that it parses back the same way.
+        let parens = leftmost_ends_stmt(expr, false);
+        if parens {
+            self.word("(");
+        }
+        self.expr_beginning_of_line(expr, true);
+        if parens {
+            self.word(")");
+        }
+        self.set_leading_attrs_printed(false);
+    }
 }
 
 pub fn add_semi(expr: &Expr) -> bool {
@@ -158,21 +216,39 @@ pub fn break_after(expr: &Expr) -> bool {
     true
 }
 
-fn remove_semi(expr: &Expr) -> bool {
+// A block-like expression statement only needs its semicolon if the
+// expression might evaluate to something other than `()`.
+//
+// Except as the last statement of a block: without the semicolon, a block,
+// unsafe block or match would become the block's tail expression, whose
+// temporaries live until after the block's local variables are dropped.
+fn remove_semi(expr: &Expr, is_last: bool) -> bool {
     match expr {
         Expr::ForLoop(_) | Expr::While(_) => true,
-        Expr::Group(group) => remove_semi(&group.expr),
-        Expr::If(expr) => match &expr.else_branch {
-            Some((_else_token, else_branch)) => remove_semi(else_branch),
-            None => true,
-        },
+        Expr::Group(group) => remove_semi(&group.expr, is_last),
+        Expr::If(expr) => {
+            block_is_unit(&expr.then_branch)
+                && match &expr.else_branch {
+                    Some((_else_token, else_branch)) => remove_semi(else_branch, is_last),
+                    None => true,
+                }
+        }
+        Expr::Block(expr) => !is_last && expr.label.is_none() && block_is_unit(&expr.block),
+        Expr::Unsafe(expr) => !is_last && block_is_unit(&expr.block),
+        Expr::Match(expr) => {
+            !is_last
+                && expr.arms.iter().all(|arm| match &*arm.body {
+                    Expr::Block(body) => body.label.is_none() && block_is_unit(&body.block),
+                    Expr::Tuple(body) => body.elems.is_empty(),
+                    _ => false,
+                })
+        }
 
         Expr::Array(_)
         | Expr::Assign(_)
         | Expr::Async(_)
         | Expr::Await(_)
         | Expr::Binary(_)
-        | Expr::Block(_)
         | Expr::Break(_)
         | Expr::Call(_)
         | Expr::Cast(_)
@@ -186,7 +262,6 @@ fn remove_semi(expr: &Expr) -> bool {
         | Expr::Lit(_)
         | Expr::Loop(_)
         | Expr::Macro(_)
-        | Expr::Match(_)
         | Expr::MethodCall(_)
         | Expr::Paren(_)
         | Expr::Path(_)
@@ -199,7 +274,6 @@ fn remove_semi(expr: &Expr) -> bool {
         | Expr::TryBlock(_)
         | Expr::Tuple(_)
         | Expr::Unary(_)
-        | Expr::Unsafe(_)
         | Expr::Verbatim(_)
         | Expr::Yield(_) => false,
 
@@ -207,3 +281,112 @@ fn remove_semi(expr: &Expr) -> bool {
         _ => false,
     }
 }
+
+// Whether the leftmost subexpression of a statement is a block-like expression
+// that the parser would take to be the entire statement. The exception is when
+// it is directly followed by `.` or `?`, which the parser does continue from.
+fn leftmost_ends_stmt(expr: &Expr, is_operand: bool) -> bool {
+    let (operand, is_operand) = match expr {
+        Expr::Assign(expr) => (&expr.left, true),
+        Expr::Binary(expr) => (&expr.left, true),
+        Expr::Call(expr) => (&expr.func, true),
+        Expr::Cast(expr) => (&expr.expr, true),
+        Expr::Index(expr) => (&expr.expr, true),
+        Expr::Range(expr) => match &expr.start {
+            Some(start) => (start, true),
+            None => return false,
+        },
+        Expr::Await(expr) => (&expr.base, false),
+        Expr::Field(expr) => (&expr.base, false),
+        Expr::MethodCall(expr) => (&expr.receiver, false),
+        Expr::Try(expr) => (&expr.expr, false),
+        Expr::Block(_)
+        | Expr::Const(_)
+        | Expr::ForLoop(_)
+        | Expr::If(_)
+        | Expr::Loop(_)
+        | Expr::Match(_)
+        | Expr::TryBlock(_)
+        | Expr::Unsafe(_)
+        | Expr::While(_) => return is_operand,
+        Expr::Macro(expr) => {
+            return is_operand && matches!(expr.mac.delimiter, MacroDelimiter::Brace(_));
+        }
+        _ => return false,
+    };
+    leftmost_ends_stmt(operand, is_operand)
+}
+
+fn block_is_unit(block: &Block) -> bool {
+    match block.stmts.last() {
+        None | Some(Stmt::Local(_) | Stmt::Item(_) | Stmt::Expr(_, Some(_))) => true,
+        Some(Stmt::Expr(expr, None)) => remove_semi(expr, false),
+        Some(Stmt::Macro(stmt)) => stmt.semi_token.is_some(),
+    }
+}
+
+// Outer attributes that come first in the statement `expr`: its own, or else
+// those of its leftmost operand, which is where the parser puts the attributes
+// of `#[attr] a = b;`.
+fn leading_attrs(mut expr: &Expr) -> &[Attribute] {
+    loop {
+        let attrs = outer_attrs(expr);
+        if attr::has_outer(attrs) {
+            return attrs;
+        }
+        expr = match expr {
+            Expr::Assign(expr) => &expr.left,
+            Expr::Binary(expr) if !binary_operand_parens(expr).0 => &expr.left,
+            Expr::Cast(expr) => &expr.expr,
+            _ => return attrs,
+        };
+    }
+}
+
+fn outer_attrs(expr: &Expr) -> &[Attribute] {
+    match expr {
+        Expr::Array(ExprArray { attrs, .. })
+        | Expr::Assign(ExprAssign { attrs, .. })
+        | Expr::Async(ExprAsync { attrs, .. })
+        | Expr::Await(ExprAwait { attrs, .. })
+        | Expr::Binary(ExprBinary { attrs, .. })
+        | Expr::Block(ExprBlock { attrs, .. })
+        | Expr::Break(ExprBreak { attrs, .. })
+        | Expr::Call(ExprCall { attrs, .. })
+        | Expr::Cast(ExprCast { attrs, .. })
+        | Expr::Closure(ExprClosure { attrs, .. })
+        | Expr::Const(ExprConst { attrs, .. })
+        | Expr::Continue(ExprContinue { attrs, .. })
+        | Expr::Field(ExprField { attrs, .. })
+        | Expr::ForLoop(ExprForLoop { attrs, .. })
+        | Expr::Group(ExprGroup { attrs, .. })
+        | Expr::If(ExprIf { attrs, .. })
+        | Expr::Index(ExprIndex { attrs, .. })
+        | Expr::Infer(ExprInfer { attrs, .. })
+        | Expr::Let(ExprLet { attrs, .. })
+        | Expr::Lit(ExprLit { attrs, .. })
+        | Expr::Loop(ExprLoop { attrs, .. })
+        | Expr::Macro(ExprMacro { attrs, .. })
+        | Expr::Match(ExprMatch { attrs, .. })
+        | Expr::MethodCall(ExprMethodCall { attrs, .. })
+        | Expr::Paren(ExprParen { attrs, .. })
+        | Expr::Path(ExprPath { attrs, .. })
+        | Expr::Range(ExprRange { attrs, .. })
+        | Expr::Reference(ExprReference { attrs, .. })
+        | Expr::Repeat(ExprRepeat { attrs, .. })
+        | Expr::Return(ExprReturn { attrs, .. })
+        | Expr::Struct(ExprStruct { attrs, .. })
+        | Expr::Try(ExprTry { attrs, .. })
+        | Expr::TryBlock(ExprTryBlock { attrs, .. })
+        | Expr::Tuple(ExprTuple { attrs, .. })
+        | Expr::Unary(ExprUnary { attrs, .. })
+        | Expr::Unsafe(ExprUnsafe { attrs, .. })
+        | Expr::While(ExprWhile { attrs, .. })
+        | Expr::Yield(ExprYield { attrs, .. }) => attrs,
+
+        Expr::Verbatim(_) => &[],
+
+        #[cfg_attr(all(test, exhaustive), deny(non_exhaustive_omitted_patterns))]
+        _ => &[],
+    }
+}
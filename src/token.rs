@@ -1,7 +1,7 @@
 use crate::algorithm::Printer;
 use proc_macro2::{Delimiter, Ident, Literal, Spacing, TokenStream, TokenTree};
 
-impl Printer {
+impl Printer<'_> {
     pub fn single_token(&mut self, token: Token, group_contents: fn(&mut Self, TokenStream)) {
         match token {
             Token::Group(delimiter, stream) => self.token_group(delimiter, stream, group_contents),
@@ -42,6 +42,13 @@ impl Printer {
         self.word(literal.to_string());
     }
 
+    // Fallback for syntax that syn only knows as raw tokens and that has no
+    // dedicated printer here.
+    pub fn verbatim_tokens(&mut self, tokens: &TokenStream) {
+        let meta = false;
+        self.attr_tokens(tokens.clone(), meta);
+    }
+
     pub fn delimiter_open(&mut self, delimiter: Delimiter) {
         self.word(match delimiter {
             Delimiter::Parenthesis => "(",
@@ -1,4 +1,5 @@
 use crate::algorithm::Printer;
+use crate::config::Edition;
 use crate::iter::IterDelimited;
 use crate::path::PathKind;
 use crate::INDENT;
@@ -9,8 +10,11 @@ use syn::{
     TypeSlice, TypeTraitObject, TypeTuple,
 };
 
-impl Printer {
+impl Printer<'_> {
     pub fn ty(&mut self, ty: &Type) {
+        if !self.enter_nested() {
+            return;
+        }
         match ty {
             Type::Array(ty) => self.type_array(ty),
             Type::BareFn(ty) => self.type_bare_fn(ty),
@@ -30,6 +34,7 @@ impl Printer {
             #[cfg_attr(all(test, exhaustive), deny(non_exhaustive_omitted_patterns))]
             _ => unimplemented!("unknown Type"),
         }
+        self.leave_nested();
     }
 
     fn type_array(&mut self, ty: &TypeArray) {
@@ -73,12 +78,15 @@ impl Printer {
 
     fn type_impl_trait(&mut self, ty: &TypeImplTrait) {
         self.word("impl ");
+        self.ibox(INDENT);
         for type_param_bound in ty.bounds.iter().delimited() {
             if !type_param_bound.is_first {
-                self.word(" + ");
+                self.space();
+                self.word("+ ");
             }
             self.type_param_bound(&type_param_bound);
         }
+        self.end();
     }
 
     fn type_infer(&mut self, ty: &TypeInfer) {
@@ -134,7 +142,9 @@ impl Printer {
     }
 
     fn type_trait_object(&mut self, ty: &TypeTraitObject) {
-        self.word("dyn ");
+        if ty.dyn_token.is_some() || self.config.edition > Edition::E2015 {
+            self.word("dyn ");
+        }
         for type_param_bound in ty.bounds.iter().delimited() {
             if !type_param_bound.is_first {
                 self.word(" + ");
@@ -251,6 +261,9 @@ impl Printer {
     pub fn return_type(&mut self, ty: &ReturnType) {
         match ty {
             ReturnType::Default => {}
+            ReturnType::Type(_arrow, ty) if self.config.strip_unit_return && is_unit(ty) => {
+                self.record("strip_unit_return", || "removed `-> ()`".to_owned());
+            }
             ReturnType::Type(_arrow, ty) => {
                 self.word(" -> ");
                 self.ty(ty);
@@ -284,3 +297,22 @@ impl Printer {
         }
     }
 }
+
+// Whether the return type is an `impl Trait` with more than one bound, which
+// can wrap between the bounds.
+pub fn returns_bound_list(output: &ReturnType) -> bool {
+    match output {
+        ReturnType::Type(_arrow, ty) => match &**ty {
+            Type::ImplTrait(ty) => ty.bounds.len() > 1,
+            _ => false,
+        },
+        ReturnType::Default => false,
+    }
+}
+
+fn is_unit(ty: &Type) -> bool {
+    match ty {
+        Type::Tuple(ty) => ty.elems.is_empty(),
+        _ => false,
+    }
+}
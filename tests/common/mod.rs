@@ -0,0 +1,9 @@
+use prettyplease::Config;
+
+// Formats `src` with `config` and checks the output against `expected`, which
+// starts with a newline so that it can be written as an indented raw string.
+pub fn test(config: &Config, src: &str, expected: &str) {
+    let file = syn::parse_file(src).unwrap();
+    let formatted = prettyplease::unparse_with_config(&file, config);
+    assert_eq!(formatted, expected.strip_prefix('\n').unwrap());
+}
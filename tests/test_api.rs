@@ -0,0 +1,102 @@
+use prettyplease::{Case, Config, QuoteStyle, TransformationEvent};
+use syn::File;
+
+#[test]
+fn test_unparse_files() {
+    let files: Vec<File> = ["fn a() {}", "struct B { x: u8 }", "use c::{d, e};"]
+        .iter()
+        .map(|src| syn::parse_file(src).unwrap())
+        .collect();
+    let formatted: Vec<String> = prettyplease::unparse_files(&files).collect();
+    assert_eq!(
+        formatted,
+        [
+            "fn a() {}\n",
+            "struct B {\n    x: u8,\n}\n",
+            "use c::{d, e};\n"
+        ],
+    );
+}
+
+#[test]
+fn test_unparse_checked() {
+    let formatted = "fn main() {\n    println!(\"hello\");\n}\n";
+    let file = syn::parse_file(formatted).unwrap();
+    let (output, changed) = prettyplease::unparse_checked(&file, formatted);
+    assert_eq!(output, formatted);
+    assert!(!changed);
+
+    let original = "fn main() { println!(\"hello\"); }";
+    let file = syn::parse_file(original).unwrap();
+    let (output, changed) = prettyplease::unparse_checked(&file, original);
+    assert_eq!(output, formatted);
+    assert!(changed);
+}
+
+#[test]
+fn test_unparse_with_report() {
+    let file = syn::parse_file(r#"const A: &str = "C:\\dir"; const B: u32 = 0xbeef;"#).unwrap();
+    let config = Config::default()
+        .quote_style(QuoteStyle::PreferRaw)
+        .hex_case(Case::Upper);
+
+    let (formatted, events) = prettyplease::unparse_with_report(&file, &config);
+    assert_eq!(
+        formatted,
+        "const A: &str = r\"C:\\dir\";\nconst B: u32 = 0xBEEF;\n"
+    );
+    assert!(events.is_empty());
+
+    let config = config.record_transformations(true);
+    let (formatted, events) = prettyplease::unparse_with_report(&file, &config);
+    assert_eq!(
+        formatted,
+        "const A: &str = r\"C:\\dir\";\nconst B: u32 = 0xBEEF;\n"
+    );
+    assert_eq!(
+        events,
+        [
+            TransformationEvent {
+                option: "quote_style",
+                description: "\"C:\\\\dir\" -> r\"C:\\dir\"".to_owned(),
+            },
+            TransformationEvent {
+                option: "hex_case",
+                description: "0xbeef -> 0xBEEF".to_owned(),
+            },
+        ],
+    );
+}
+
+#[test]
+fn test_unparse_owned() {
+    let formatted = prettyplease::unparse_owned(syn::parse_file("struct S;").unwrap());
+    assert_eq!(formatted, "struct S;\n");
+}
+
+#[test]
+fn test_unparse_signature() {
+    let item: syn::ItemFn = syn::parse_str("fn f(x: u8) -> u8 { x }").unwrap();
+    assert_eq!(
+        prettyplease::unparse_signature(&item.sig),
+        "fn f(x: u8) -> u8"
+    );
+
+    let item: syn::ItemFn = syn::parse_str(
+        "pub async unsafe extern \"C\" fn maximally_generic_function<'a, T: Clone + Send + 'a, const N: usize>(first_argument: &'a mut [T; N], second: impl Fn(T) -> T) -> Result<Vec<T>, Box<dyn Error + Send + Sync>> where T: Default {}",
+    )
+    .unwrap();
+    assert_eq!(
+        prettyplease::unparse_signature(&item.sig),
+        "async unsafe extern \"C\" fn maximally_generic_function<
+    'a,
+    T: Clone + Send + 'a,
+    const N: usize,
+>(
+    first_argument: &'a mut [T; N],
+    second: impl Fn(T) -> T,
+) -> Result<Vec<T>, Box<dyn Error + Send + Sync>>
+where
+    T: Default,",
+    );
+}
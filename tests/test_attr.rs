@@ -0,0 +1,233 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::{Config, DocStyle};
+
+#[test]
+fn test_nested_meta_list() {
+    test(
+        &Config::default(),
+        r#"
+#[serde(rename_all = "camelCase", deny_unknown_fields, default, bound(serialize = "T: MyTrait"))]
+#[cfg(all(feature = "some_long_feature_name", feature = "another_long_feature_name", unix))]
+struct S;
+"#,
+        r#"
+#[serde(
+    rename_all = "camelCase",
+    deny_unknown_fields,
+    default,
+    bound(serialize = "T: MyTrait"),
+)]
+#[cfg(
+    all(feature = "some_long_feature_name", feature = "another_long_feature_name", unix)
+)]
+struct S;
+"#,
+    );
+}
+
+#[test]
+fn test_other_token_list() {
+    test(
+        &Config::default(),
+        "
+#[my_attr(1111111111, 2222222222, 3333333333, 4444444444, 5555555555, 6666666666, 77777777)]
+struct S;
+",
+        r#"
+#[my_attr(
+    1111111111,
+    2222222222,
+    3333333333,
+    4444444444,
+    5555555555,
+    6666666666,
+    77777777
+)]
+struct S;
+"#,
+    );
+}
+
+const DOCS: &str = "
+/// First line
+/// second line
+fn f() {}
+/** Block
+  comment */
+fn g() {}
+";
+
+#[test]
+fn test_doc_comment_style() {
+    test(
+        &Config::default(),
+        DOCS,
+        "
+/// First line
+/// second line
+fn f() {}
+/** Block
+  comment */
+fn g() {}
+",
+    );
+    test(
+        &Config::default().doc_comment_style(DocStyle::LineComments),
+        DOCS,
+        "
+/// First line
+/// second line
+fn f() {}
+/// Block
+///  comment
+fn g() {}
+",
+    );
+    test(
+        &Config::default().doc_comment_style(DocStyle::BlockComments),
+        DOCS,
+        "
+/** First line */
+/** second line */
+fn f() {}
+/**
+ * Block
+ *  comment
+ */
+fn g() {}
+",
+    );
+}
+
+#[test]
+fn test_marker_attributes() {
+    let src = "
+#[non_exhaustive]
+pub enum E {}
+#[non_exhaustive]
+#[repr(transparent)]
+pub struct S(u8);
+";
+    test(&Config::default(), src, src);
+}
+
+#[test]
+fn test_sort_attributes() {
+    let expected = "
+/// Docs first.
+/// More docs.
+#[allow(dead_code)]
+#[derive(Clone)]
+#[derive(Debug)]
+#[serde(rename = \"b\")]
+struct S;
+";
+    let config = Config::default().sort_attributes(true);
+    test(
+        &config,
+        "
+/// Docs first.
+#[serde(rename = \"b\")]
+#[derive(Debug)]
+/// More docs.
+#[allow(dead_code)]
+#[derive(Clone)]
+struct S;
+",
+        expected,
+    );
+    test(
+        &config,
+        "
+#[derive(Clone)]
+/// Docs first.
+#[allow(dead_code)]
+#[derive(Debug)]
+/// More docs.
+#[serde(rename = \"b\")]
+struct S;
+",
+        expected,
+    );
+}
+
+#[test]
+fn test_sort_lint_attributes() {
+    let src = "
+#[allow(unused, dead_code, clippy::too_many_lines, clippy::all)]
+#[deny(warnings, unsafe_code)]
+#[cfg(b, a)]
+fn f() {}
+";
+    test(&Config::default(), src, src);
+    test(
+        &Config::default().sort_lint_attributes(true),
+        src,
+        "
+#[allow(clippy::all, clippy::too_many_lines, dead_code, unused)]
+#[deny(unsafe_code, warnings)]
+#[cfg(b, a)]
+fn f() {}
+",
+    );
+}
+
+const INNER_ATTRS: &str = "
+#![allow(dead_code)]
+#![deny(warnings)]
+use std::io;
+mod m {
+    #![allow(x)]
+    fn f() {}
+}
+";
+
+#[test]
+fn test_blank_after_inner_attrs() {
+    test(
+        &Config::default(),
+        INNER_ATTRS,
+        "
+#![allow(dead_code)]
+#![deny(warnings)]
+
+use std::io;
+mod m {
+    #![allow(x)]
+
+    fn f() {}
+}
+",
+    );
+    test(
+        &Config::default().blank_after_inner_attrs(false),
+        INNER_ATTRS,
+        INNER_ATTRS,
+    );
+}
+
+#[test]
+fn test_doc_comments_on_own_line() {
+    // Ordinary comments are not in the syntax tree. Doc comments never share
+    // a line with code, so they cannot push it past the margin.
+    test(
+        &Config::default(),
+        "
+struct S {
+    #[doc = \" The width.\"] w: u32,
+    /// The height, which is described by a long comment that goes past the margin when put after the field.
+    h: u32,
+}
+",
+        "
+struct S {
+    /// The width.
+    w: u32,
+    /// The height, which is described by a long comment that goes past the margin when put after the field.
+    h: u32,
+}
+",
+    );
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::Config;
+
+#[test]
+fn test_await_method_chain() {
+    test(
+        &Config::default(),
+        "
+async fn f() {
+    let value = client.request(url).header(key, value).send().await?.json::<Response>().await?;
+}
+",
+        "
+async fn f() {
+    let value = client
+        .request(url)
+        .header(key, value)
+        .send().await?
+        .json::<Response>().await?;
+}
+",
+    );
+}
+
+#[test]
+fn test_await_call() {
+    test(
+        &Config::default(),
+        "
+async fn f() {
+    let response = some_module::some_long_function_name(first_argument, second_arg).await?;
+}
+",
+        "
+async fn f() {
+    let response = some_module::some_long_function_name(
+        first_argument,
+        second_arg,
+    ).await?;
+}
+",
+    );
+}
+
+#[test]
+fn test_await_field() {
+    test(
+        &Config::default(),
+        "
+async fn f() {
+    let response_value = self.client_connection_pool_handle.connection_field_xyz_abcd.await?;
+}
+",
+        "
+async fn f() {
+    let response_value = self
+        .client_connection_pool_handle
+        .connection_field_xyz_abcd.await?;
+}
+",
+    );
+}
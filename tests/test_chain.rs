@@ -0,0 +1,140 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::Config;
+
+#[test]
+fn test_associated_fn_receiver() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    let builder = Builder::new().first_option(1).second_option(2).third_option(3).fourth_option(4);
+    let builder = Self::new().first_option(1).second_option(2).third_option(3).fourth_option(44);
+    let b = Builder::new().a().b().c().d();
+}
+",
+        "
+fn f() {
+    let builder = Builder::new()
+        .first_option(1)
+        .second_option(2)
+        .third_option(3)
+        .fourth_option(4);
+    let builder = Self::new()
+        .first_option(1)
+        .second_option(2)
+        .third_option(3)
+        .fourth_option(44);
+    let b = Builder::new().a().b().c().d();
+}
+",
+    );
+}
+
+#[test]
+fn test_continuation_indent() {
+    test(
+        &Config::default().continuation_indent(8),
+        "
+fn f() {
+    if x {
+        let value = receiver.first_method_call(argument).second_method_call().third_method();
+    }
+}
+",
+        "
+fn f() {
+    if x {
+        let value = receiver
+                .first_method_call(argument)
+                .second_method_call()
+                .third_method();
+    }
+}
+",
+    );
+}
+
+#[test]
+fn test_turbofish_and_args() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    let w = value.convert::<SomeLongTypeName, AnotherLongTypeName>(first_argument, second_argument, third);
+    let x = value.convert::<SomeLongTypeName, AnotherLongTypeName, YetAnotherLongTypeName, MoreTypes>(first_argument, second_argument);
+}
+",
+        "
+fn f() {
+    let w = value
+        .convert::<SomeLongTypeName, AnotherLongTypeName>(
+            first_argument,
+            second_argument,
+            third,
+        );
+    let x = value
+        .convert::<
+            SomeLongTypeName,
+            AnotherLongTypeName,
+            YetAnotherLongTypeName,
+            MoreTypes,
+        >(first_argument, second_argument);
+}
+",
+    );
+}
+
+#[test]
+fn test_block_and_struct_receivers() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    { a }.method();
+    Foo { x: 1 }.method();
+    let v = { compute_something_long(first_argument); second_statement_value }.into_iter();
+    let w = Foo { first_field_name: first_value, second_field_name: second_value }.build();
+    if (Foo { x: 1 }).is_valid() {}
+}
+",
+        "
+fn f() {
+    { a }.method();
+    Foo { x: 1 }.method();
+    let v = {
+        compute_something_long(first_argument);
+        second_statement_value
+    }.into_iter();
+    let w = Foo {
+        first_field_name: first_value,
+        second_field_name: second_value,
+    }.build();
+    if (Foo { x: 1 }).is_valid() {}
+}
+",
+    );
+}
+
+#[test]
+fn test_parenthesized_block_receiver() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    let value = (loop { break 1; }).method_one().method_two_with_long_name().method_three_with_longer_name();
+}
+",
+        "
+fn f() {
+    let value = (loop {
+        break 1;
+    })
+        .method_one()
+        .method_two_with_long_name()
+        .method_three_with_longer_name();
+}
+",
+    );
+}
@@ -0,0 +1,166 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::Config;
+
+#[test]
+fn test_tuple_struct() {
+    test(
+        &Config::default(),
+        "
+pub struct Triple<T>(pub VeryLongTypeNameNumberOne<T>, pub(crate) VeryLongTypeNameNumberTwo, Option<T>) where T: Clone;
+struct Newtype(u8);
+struct Short(u8, u16, u32);
+",
+        "
+pub struct Triple<T>(
+    pub VeryLongTypeNameNumberOne<T>,
+    pub(crate) VeryLongTypeNameNumberTwo,
+    Option<T>,
+)
+where
+    T: Clone;
+struct Newtype(u8);
+struct Short(u8, u16, u32);
+",
+    );
+}
+
+const FIELDS: &str = "
+struct S { zeta: u8, alpha: u16, mid: u32 }
+#[repr(C)]
+struct C { zeta: u8, alpha: u16 }
+struct T(u8, u16);
+enum E { V { zeta: u8, alpha: u16 }, W(u8, u16) }
+";
+
+#[test]
+fn test_fields_in_source_order_by_default() {
+    test(
+        &Config::default(),
+        FIELDS,
+        "
+struct S {
+    zeta: u8,
+    alpha: u16,
+    mid: u32,
+}
+#[repr(C)]
+struct C {
+    zeta: u8,
+    alpha: u16,
+}
+struct T(u8, u16);
+enum E {
+    V { zeta: u8, alpha: u16 },
+    W(u8, u16),
+}
+",
+    );
+}
+
+#[test]
+fn test_reorder_fields() {
+    test(
+        &Config::default().reorder_fields(true),
+        FIELDS,
+        "
+struct S {
+    alpha: u16,
+    mid: u32,
+    zeta: u8,
+}
+#[repr(C)]
+struct C {
+    zeta: u8,
+    alpha: u16,
+}
+struct T(u8, u16);
+enum E {
+    V { alpha: u16, zeta: u8 },
+    W(u8, u16),
+}
+",
+    );
+}
+
+#[test]
+fn test_discriminant_with_fields() {
+    let src = "
+#[repr(u8)]
+enum E {
+    A(u8) = 1,
+    B { x: u16 } = 2,
+    C = 3,
+}
+";
+    test(&Config::default(), src, src);
+}
+
+#[test]
+fn test_generics_and_where_clause() {
+    test(
+        &Config::default(),
+        "
+struct S<T, U> where T: Clone + Send + Sync + 'static, U: Iterator<Item = T> + DoubleEndedIterator { t: T, u: U }
+enum E<T> where T: Clone { A(T), B }
+",
+        "
+struct S<T, U>
+where
+    T: Clone + Send + Sync + 'static,
+    U: Iterator<Item = T> + DoubleEndedIterator,
+{
+    t: T,
+    u: U,
+}
+enum E<T> where T: Clone {
+    A(T),
+    B,
+}
+",
+    );
+}
+
+#[test]
+fn test_pack_unit_variants() {
+    let config = Config::default().pack_unit_variants(true);
+    test(
+        &config,
+        "
+enum Color { Black, Red, Green, Yellow, Blue, Magenta, Cyan, White, BrightBlack, BrightRed, BrightGreen, BrightYellow }
+enum G { A(u8), B }
+",
+        "
+enum Color {
+    Black, Red, Green, Yellow, Blue, Magenta, Cyan, White, BrightBlack, BrightRed,
+    BrightGreen, BrightYellow,
+}
+enum G {
+    A(u8),
+    B,
+}
+",
+    );
+}
+
+#[test]
+fn test_union() {
+    test(
+        &Config::default(),
+        "
+union U { a: u32, b: f32 }
+union LongUnion { first_field_with_long_name: SomeLongType<u32>, second_field_with_long_name: [u8; 64] }
+",
+        "
+union U {
+    a: u32,
+    b: f32,
+}
+union LongUnion {
+    first_field_with_long_name: SomeLongType<u32>,
+    second_field_with_long_name: [u8; 64],
+}
+",
+    );
+}
@@ -0,0 +1,11 @@
+#[test]
+fn test_unparse_debug() {
+    let file = syn::parse_file("fn f(x: u8) -> u8 { x + 1 }").unwrap();
+    let formatted = prettyplease::unparse_debug(&file);
+    let expected = "
+««fn f(·«·x: u8·»)« -> u8» {·
+‹‹‹    x›· + 1››·
+»}·
+»";
+    assert_eq!(formatted, expected.strip_prefix('\n').unwrap());
+}
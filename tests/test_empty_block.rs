@@ -0,0 +1,61 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::{Config, EmptyBlock};
+
+const SRC: &str = "
+fn f() {}
+struct S {}
+fn g() {
+    if a {}
+    if b {}
+}
+";
+
+#[test]
+fn test_compact() {
+    test(
+        &Config::default(),
+        SRC,
+        "
+fn f() {}
+struct S {}
+fn g() {
+    if a {}
+    if b {}
+}
+",
+    );
+}
+
+#[test]
+fn test_spaced() {
+    test(
+        &Config::default().empty_block_style(EmptyBlock::Spaced),
+        SRC,
+        "
+fn f() { }
+struct S { }
+fn g() {
+    if a { }
+    if b { }
+}
+",
+    );
+}
+
+#[test]
+fn test_spaced_after_broken_header() {
+    test(
+        &Config::default().empty_block_style(EmptyBlock::Spaced),
+        "
+trait Foo: Aaaaaaaaaaaaaaaaaaaa + Bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb + Ccccccccccccccccccccccc + Ddddddddddd {}
+",
+        "
+trait Foo:
+    Aaaaaaaaaaaaaaaaaaaa + Bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb + Ccccccccccccccccccccccc
+    + Ddddddddddd
+{ }
+",
+    );
+}
@@ -0,0 +1,584 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::{Config, RangeSpacing};
+
+#[test]
+fn test_repeat() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    let a = [0; 5];
+    let c = [some_function_with_a_long_name(first_argument, second_argument, third_arg); 100];
+}
+",
+        "
+fn f() {
+    let a = [0; 5];
+    let c = [
+        some_function_with_a_long_name(first_argument, second_argument, third_arg);
+        100
+    ];
+}
+",
+    );
+}
+
+#[test]
+fn test_let_expr() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    if let Some(x) = a && let Some(y) = b && x == y {}
+    let m = matches(let Some(x) = value);
+    if let Some(first_long_binding) = compute_first_value(argument_one) && let Some(second) = other_value() {}
+}
+",
+        "
+fn f() {
+    if let Some(x) = a && let Some(y) = b && x == y {}
+    let m = matches(let Some(x) = value);
+    if let Some(first_long_binding) = compute_first_value(argument_one)
+        && let Some(second) = other_value()
+    {}
+}
+",
+    );
+}
+
+const BLOCKS: &str = "
+fn f() {
+    let c = |x| x + 1;
+    let d = |x| { x + 1 };
+    match x { A => { 1 } B => 2 }
+    unsafe { g() }
+}
+";
+
+#[test]
+fn test_small_blocks() {
+    test(
+        &Config::default(),
+        BLOCKS,
+        "
+fn f() {
+    let c = |x| x + 1;
+    let d = |x| { x + 1 };
+    match x {
+        A => 1,
+        B => 2,
+    }
+    unsafe { g() }
+}
+",
+    );
+}
+
+#[test]
+fn test_force_multiline_blocks() {
+    test(
+        &Config::default().force_multiline_blocks(true),
+        BLOCKS,
+        "
+fn f() {
+    let c = |x| x + 1;
+    let d = |x| {
+        x + 1
+    };
+    match x {
+        A => {
+            1
+        }
+        B => 2,
+    }
+    unsafe {
+        g()
+    }
+}
+",
+    );
+}
+
+#[test]
+fn test_else_if_chain() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    if first_condition_that_is_long(argument) { one() } else if second_condition_that_is_long(argument) { two() } else { three() }
+    let x = if a { 1 } else if b { 2 } else { 3 };
+}
+",
+        "
+fn f() {
+    if first_condition_that_is_long(argument) {
+        one()
+    } else if second_condition_that_is_long(argument) {
+        two()
+    } else {
+        three()
+    }
+    let x = if a { 1 } else if b { 2 } else { 3 };
+}
+",
+    );
+}
+
+#[test]
+fn test_try_block() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    let r: Result<(), E> = try { do_thing()?; Ok(()) };
+    let s = try { a? };
+    let t = try { some_long_function_name(argument_one, argument_two)?.another_method_call(argument_three)? };
+}
+",
+        "
+fn f() {
+    let r: Result<(), E> = try {
+        do_thing()?;
+        Ok(())
+    };
+    let s = try { a? };
+    let t = try {
+        some_long_function_name(argument_one, argument_two)?
+            .another_method_call(argument_three)?
+    };
+}
+",
+    );
+}
+
+#[test]
+fn test_range_long_bounds() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    for i in self.compute_start_index_for(&something)..self.compute_end_index_for(&something_else) {}
+    let r = compute_start_of_range(argument).saturating_sub(1)..=compute_end_of_range(argument).min(limit_value);
+    let s = a.start()..b.end();
+}
+",
+        "
+fn f() {
+    for i in self.compute_start_index_for(&something)
+        ..self.compute_end_index_for(&something_else)
+    {}
+    let r = compute_start_of_range(argument).saturating_sub(1)
+        ..=compute_end_of_range(argument).min(limit_value);
+    let s = a.start()..b.end();
+}
+",
+    );
+}
+
+#[test]
+fn test_match_breaks_context() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    let x = match y { Some(v) => v, None => 0 };
+    call(match y { A => 1, B => 2 });
+}
+",
+        "
+fn f() {
+    let x = match y {
+        Some(v) => v,
+        None => 0,
+    };
+    call(
+        match y {
+            A => 1,
+            B => 2,
+        },
+    );
+}
+",
+    );
+}
+
+#[test]
+fn test_assign_long_rhs() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    self.some_field_name = self.builder.with_option(first_argument).with_other(second_argument).build();
+    total_accumulated_value += compute_the_next_value(first_argument, second_argument, third);
+    self.config = Config { first_field: first_value, second_field: second_value, third: 3 };
+}
+",
+        "
+fn f() {
+    self.some_field_name = self
+        .builder
+        .with_option(first_argument)
+        .with_other(second_argument)
+        .build();
+    total_accumulated_value += compute_the_next_value(
+        first_argument,
+        second_argument,
+        third,
+    );
+    self.config = Config {
+        first_field: first_value,
+        second_field: second_value,
+        third: 3,
+    };
+}
+",
+    );
+}
+
+#[test]
+fn test_for_loop_destructuring() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    for (key, value) in self.some_collection.iter().filter(|entry| entry.is_interesting()).take(limit) {}
+    for (a, b) in x {}
+}
+",
+        "
+fn f() {
+    for (key, value) in self
+        .some_collection
+        .iter()
+        .filter(|entry| entry.is_interesting())
+        .take(limit)
+    {}
+    for (a, b) in x {}
+}
+",
+    );
+}
+
+const STRUCT_LITERALS: &str = "
+fn f() {
+    let a = Point { x: 1, y: 2 };
+    let b = Point {
+        x: 1,
+        y: 2,
+    };
+    let c = Point { x: 1, y: 2, };
+}
+";
+
+#[test]
+fn test_struct_lit_heuristic() {
+    test(
+        &Config::default(),
+        STRUCT_LITERALS,
+        "
+fn f() {
+    let a = Point { x: 1, y: 2 };
+    let b = Point { x: 1, y: 2 };
+    let c = Point { x: 1, y: 2 };
+}
+",
+    );
+    test(
+        &Config::default().struct_lit_heuristic(true),
+        STRUCT_LITERALS,
+        "
+fn f() {
+    let a = Point { x: 1, y: 2 };
+    let b = Point {
+        x: 1,
+        y: 2,
+    };
+    let c = Point {
+        x: 1,
+        y: 2,
+    };
+}
+",
+    );
+}
+
+#[test]
+fn test_match_arms() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    match x {
+        A   =>   1,
+        B => { foo(); 2 },
+        C if guard(c) => 3,
+        D if cond => { bar() }
+        _ => {}
+    }
+}
+",
+        "
+fn f() {
+    match x {
+        A => 1,
+        B => {
+            foo();
+            2
+        }
+        C if guard(c) => 3,
+        D if cond => bar(),
+        _ => {}
+    }
+}
+",
+    );
+}
+
+#[test]
+fn test_negative_literals() {
+    let src = "
+fn f() {
+    let a = -1;
+    let b = -1.5f64;
+    let c = -0x10;
+    let g = (-1i32).abs();
+}
+";
+    test(&Config::default(), src, src);
+}
+
+#[test]
+fn test_array_of_struct_literals() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    let points = [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }, Point { x: 5, y: 6 }];
+    let configs = [Config { first_field_name: first_value, second_field_name: second_value }, Config { first_field_name: 1, second_field_name: 2 }, Config::default()];
+}
+",
+        "
+fn f() {
+    let points = [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }, Point { x: 5, y: 6 }];
+    let configs = [
+        Config {
+            first_field_name: first_value,
+            second_field_name: second_value,
+        },
+        Config {
+            first_field_name: 1,
+            second_field_name: 2,
+        },
+        Config::default(),
+    ];
+}
+",
+    );
+}
+
+#[test]
+fn test_closure_typed_params() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    let callback = move |first_parameter: &mut SomeType, second_parameter: Option<u32>, third: &str| -> bool { true };
+    let g = |x: i32, y: i32| x + y;
+}
+",
+        "
+fn f() {
+    let callback = move |
+        first_parameter: &mut SomeType,
+        second_parameter: Option<u32>,
+        third: &str,
+    | -> bool { true };
+    let g = |x: i32, y: i32| x + y;
+}
+",
+    );
+}
+
+#[test]
+fn test_labeled_break_continue() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    'outer: loop {
+        'inner: for x in y {
+            if a { continue 'inner; }
+            if b { break 'outer compute_the_result_value(first_argument, second_argument, third_argument); }
+            break 'outer;
+        }
+        break 'outer value;
+    }
+}
+",
+        "
+fn f() {
+    'outer: loop {
+        'inner: for x in y {
+            if a {
+                continue 'inner;
+            }
+            if b {
+                break 'outer compute_the_result_value(
+                    first_argument,
+                    second_argument,
+                    third_argument,
+                );
+            }
+            break 'outer;
+        }
+        break 'outer value;
+    }
+}
+",
+    );
+}
+
+#[test]
+fn test_if_let_destructuring() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    if let Foo { first_field, second_field, .. } = self.compute_the_scrutinee_value(first_argument, second) {}
+    if let Foo { a, b } = value {}
+}
+",
+        "
+fn f() {
+    if let Foo { first_field, second_field, .. } =
+        self.compute_the_scrutinee_value(first_argument, second)
+    {}
+    if let Foo { a, b } = value {}
+}
+",
+    );
+}
+
+#[test]
+fn test_tuple_index() {
+    let src = "
+fn h() {
+    let a = x.0;
+    let b = x.0.1;
+    let c = (a, b).0;
+    let d = x.0.1.2.field.3;
+}
+";
+    test(&Config::default(), src, src);
+    test(
+        &Config::default(),
+        "
+fn h() {
+    let e = x.0 .1;
+}
+",
+        "
+fn h() {
+    let e = x.0.1;
+}
+",
+    );
+}
+
+#[test]
+fn test_align_match_arms() {
+    test(
+        &Config::default().align_match_arms(true),
+        "
+fn f() {
+    match x {
+        A => 1,
+        BBB => 2,
+        CC => 3,
+        SomeLongerPatternName(first, second) => compute_the_value(first, second, third_argument),
+    }
+    match y {
+        A => 1,
+        LongerPattern => 2,
+        B => compute_the_value_of_something(first_argument, second_argument, third_arg),
+        C => 3,
+    }
+}
+",
+        "
+fn f() {
+    match x {
+        A   => 1,
+        BBB => 2,
+        CC  => 3,
+        SomeLongerPatternName(first, second) => {
+            compute_the_value(first, second, third_argument)
+        }
+    }
+    match y {
+        A             => 1,
+        LongerPattern => 2,
+        B => compute_the_value_of_something(first_argument, second_argument, third_arg),
+        C => 3,
+    }
+}
+",
+    );
+}
+
+const RANGES: &str = "
+fn f() {
+    match n {
+        0..=9 => {}
+        10.. => {}
+        ..=-1 => {}
+        _ => {}
+    }
+    let r = a..=b;
+    let s = ..x;
+    let t = (a + 1)..(b - 1);
+}
+";
+
+#[test]
+fn test_range_spacing() {
+    test(&Config::default(), RANGES, RANGES);
+    // Only ranges with both bounds are spaced.
+    test(
+        &Config::default().range_spacing(RangeSpacing::Spaced),
+        RANGES,
+        "
+fn f() {
+    match n {
+        0 ..= 9 => {}
+        10.. => {}
+        ..=-1 => {}
+        _ => {}
+    }
+    let r = a ..= b;
+    let s = ..x;
+    let t = (a + 1) .. (b - 1);
+}
+",
+    );
+}
+
+#[test]
+fn test_cast_continuation_indent() {
+    test(
+        &Config::default().continuation_indent(8),
+        "
+fn f() {
+    let value = some_function_with_a_long_name(first_argument_value, second_argument) as u64;
+}
+",
+        "
+fn f() {
+    let value = some_function_with_a_long_name(first_argument_value, second_argument)
+            as u64;
+}
+",
+    );
+}
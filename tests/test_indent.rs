@@ -0,0 +1,32 @@
+use prettyplease::Config;
+
+fn format(config: &Config, src: &str) -> String {
+    let file = syn::parse_file(src).unwrap();
+    let formatted = prettyplease::unparse_with_config(&file, config);
+    syn::parse_file(&formatted).unwrap();
+    formatted
+}
+
+// Boxes with a negative offset, like the one around the return type of a
+// function pointer or the left operand of a binary operator, nested deeper
+// than the margin is wide.
+#[test]
+fn test_pathological_nesting() {
+    let ty = format!(
+        "type T = {}u8{};",
+        "Box<dyn Fn(Vec<Aaaaaaaaaaaa>, ".repeat(15),
+        ") -> Bbbbbbbbbbbbbb>".repeat(15),
+    );
+    let expr = format!(
+        "const C: u8 = {}aaaaaaaaaaaa{};",
+        "(bbbbbbbbbbbb as u8 + cccccccccccc.dddddddddddd(".repeat(15),
+        ") as u16 - eeeeeeeeeeee)".repeat(15),
+    );
+    for src in [ty, expr] {
+        for indent in [0, 4, 8] {
+            let config = Config::default().continuation_indent(indent);
+            let formatted = format(&config, &src);
+            assert!(formatted.lines().count() > 15);
+        }
+    }
+}
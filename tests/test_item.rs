@@ -0,0 +1,291 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::Config;
+
+#[test]
+fn test_impl_long_header() {
+    test(
+        &Config::default(),
+        "
+impl<SomeLongTypeParameter: Clone + Debug, AnotherLongTypeParameter: Iterator<Item = u8>> SomeTrait<SomeLongTypeParameter> for SomeType<AnotherLongTypeParameter> where SomeLongTypeParameter: Send {
+    fn first(&self) -> u8 { 1 }
+    fn second(&self) -> u8 { 2 }
+    fn third(&self) -> u8 { 3 }
+}
+",
+        "
+impl<
+    SomeLongTypeParameter: Clone + Debug,
+    AnotherLongTypeParameter: Iterator<Item = u8>,
+> SomeTrait<SomeLongTypeParameter> for SomeType<AnotherLongTypeParameter>
+where
+    SomeLongTypeParameter: Send,
+{
+    fn first(&self) -> u8 {
+        1
+    }
+    fn second(&self) -> u8 {
+        2
+    }
+    fn third(&self) -> u8 {
+        3
+    }
+}
+",
+    );
+}
+
+#[test]
+fn test_receivers() {
+    let src = "
+impl S {
+    fn a(self) {}
+    fn b(mut self) {}
+    fn c(&self) {}
+    fn d(&mut self) {}
+    fn e(&'a self) {}
+    fn f(&'a mut self) {}
+    fn g(self: Box<Self>) {}
+    fn h(self: Pin<&mut Self>) {}
+    fn i(mut self: Rc<Self>) {}
+}
+";
+    test(&Config::default(), src, src);
+    let formatted = prettyplease::unparse(&syn::parse_file(src).unwrap());
+    syn::parse_file(&formatted).unwrap();
+}
+
+#[test]
+fn test_aliases() {
+    test(
+        &Config::default(),
+        "
+type Callback<T: Send + Sync + 'static> = Box<dyn Fn(T) + Send>;
+trait ShortAlias = Send + Sync;
+trait LongTraitAliasName<T> = Iterator<Item = T> + DoubleEndedIterator + ExactSizeIterator + Send + Sync + 'static;
+",
+        "
+type Callback<T: Send + Sync + 'static> = Box<dyn Fn(T) + Send>;
+trait ShortAlias = Send + Sync;
+trait LongTraitAliasName<T> = Iterator<Item = T>
+    + DoubleEndedIterator
+    + ExactSizeIterator
+    + Send
+    + Sync
+    + 'static;
+",
+    );
+}
+
+const UNIT_RETURNS: &str = "
+fn a() -> () {}
+fn b() -> ((),) {}
+fn c(f: fn() -> ()) -> impl Fn() -> () {}
+trait T {
+    fn d() -> ();
+}
+";
+
+#[test]
+fn test_strip_unit_return() {
+    test(
+        &Config::default(),
+        UNIT_RETURNS,
+        "
+fn a() {}
+fn b() -> ((),) {}
+fn c(f: fn()) -> impl Fn() {}
+trait T {
+    fn d();
+}
+",
+    );
+    test(
+        &Config::default().strip_unit_return(false),
+        UNIT_RETURNS,
+        UNIT_RETURNS,
+    );
+}
+
+#[test]
+fn test_generic_param_order() {
+    test(
+        &Config::default(),
+        "
+struct S<T: Clone = u8, 'a, const N: usize = 3, 'b: 'a>(&'a T, &'b [u8; N]);
+fn f<T, 'a, const N: usize>() {}
+",
+        "
+struct S<'a, 'b: 'a, T: Clone = u8, const N: usize = 3>(&'a T, &'b [u8; N]);
+fn f<'a, T, const N: usize>() {}
+",
+    );
+}
+
+#[test]
+fn test_const_long_type_and_initializer() {
+    test(
+        &Config::default(),
+        "
+impl S {
+    const LOOKUP_TABLE_FOR_SOMETHING: HashMap<&'static str, Vec<(u32, Option<String>)>> = HashMap::new();
+    const DEFAULT_CONFIGURATION: Config = Config::new().with_option(first_value).with_other(second);
+}
+static mut COUNTER_OF_ALL_THE_THINGS_THAT_HAPPENED: AtomicUsize = AtomicUsize::new(initial_value());
+",
+        "
+impl S {
+    const LOOKUP_TABLE_FOR_SOMETHING: HashMap<&'static str, Vec<(u32, Option<String>)>> =
+        HashMap::new();
+    const DEFAULT_CONFIGURATION: Config = Config::new()
+        .with_option(first_value)
+        .with_other(second);
+}
+static mut COUNTER_OF_ALL_THE_THINGS_THAT_HAPPENED: AtomicUsize = AtomicUsize::new(
+    initial_value(),
+);
+",
+    );
+}
+
+#[test]
+fn test_const_long_binary_initializer() {
+    test(
+        &Config::default(),
+        "
+const FOO_BAR_BAZ_QUUX_CORGE_GRAULT: SomeVeryLongTypeName<Generic, Another> = some_function_name_here + 1;
+const SHORT: u32 = FIRST_CONSTANT_WITH_A_LONG_NAME * SECOND_CONSTANT_WITH_A_LONG_NAME + THIRD_CONSTANT;
+",
+        "
+const FOO_BAR_BAZ_QUUX_CORGE_GRAULT: SomeVeryLongTypeName<Generic, Another> =
+    some_function_name_here + 1;
+const SHORT: u32 =
+    FIRST_CONSTANT_WITH_A_LONG_NAME * SECOND_CONSTANT_WITH_A_LONG_NAME + THIRD_CONSTANT;
+",
+    );
+}
+
+#[test]
+fn test_negative_and_unsafe_impls() {
+    let src = "
+impl !Send for T {}
+unsafe impl Send for T {}
+unsafe impl<T: Send> Sync for Wrapper<T> {}
+impl<T> !Sync for Other<T> {}
+";
+    test(&Config::default(), src, src);
+}
+
+#[test]
+fn test_parameter_trailing_comma() {
+    test(
+        &Config::default(),
+        r#"
+fn function_with_many_parameters(first_parameter: u32, second_parameter: String, third: Vec<u8>) {}
+extern "C" {
+    fn printf_with_a_rather_long_name(format_string: *const c_char, another_argument: i32, ...) -> c_int;
+    fn short(fmt: *const c_char, ...);
+}
+"#,
+        r#"
+fn function_with_many_parameters(
+    first_parameter: u32,
+    second_parameter: String,
+    third: Vec<u8>,
+) {}
+extern "C" {
+    fn printf_with_a_rather_long_name(
+        format_string: *const c_char,
+        another_argument: i32,
+        ...
+    ) -> c_int;
+    fn short(fmt: *const c_char, ...);
+}
+"#,
+    );
+}
+
+#[test]
+fn test_long_generics_and_params() {
+    test(
+        &Config::default(),
+        "
+pub fn maximally_overflowing_function<TFirstParameter: Clone + Send + Sync + 'static, USecondParameter: Iterator<Item = TFirstParameter>>(first_argument: HashMap<String, Vec<TFirstParameter>>, second_argument: USecondParameter) -> Result<BTreeMap<String, Vec<TFirstParameter>>, Box<dyn std::error::Error + Send + Sync>> where TFirstParameter: Default + Debug, USecondParameter: ExactSizeIterator { body() }
+",
+        "
+pub fn maximally_overflowing_function<
+    TFirstParameter: Clone + Send + Sync + 'static,
+    USecondParameter: Iterator<Item = TFirstParameter>,
+>(
+    first_argument: HashMap<String, Vec<TFirstParameter>>,
+    second_argument: USecondParameter,
+) -> Result<
+    BTreeMap<String, Vec<TFirstParameter>>,
+    Box<dyn std::error::Error + Send + Sync>,
+>
+where
+    TFirstParameter: Default + Debug,
+    USecondParameter: ExactSizeIterator,
+{
+    body()
+}
+",
+    );
+}
+
+#[test]
+fn test_supertraits_and_generics() {
+    test(
+        &Config::default(),
+        "
+pub trait SomeLongTraitName<TFirstParameter, USecondParameter>: Clone + Debug + Send + Sync + 'static where TFirstParameter: Default { fn f(&self); }
+pub trait AnotherTraitName<T>: SomeSupertraitWithALongName + AnotherSupertraitWithALongName<T> + Send + Sync + 'static {}
+",
+        "
+pub trait SomeLongTraitName<TFirstParameter, USecondParameter>:
+    Clone + Debug + Send + Sync + 'static
+where
+    TFirstParameter: Default,
+{
+    fn f(&self);
+}
+pub trait AnotherTraitName<T>:
+    SomeSupertraitWithALongName + AnotherSupertraitWithALongName<T> + Send + Sync
+    + 'static
+{}
+",
+    );
+}
+
+const GENERIC_LISTS: &str = "
+struct S<
+    A,
+    B,
+>(A, B);
+struct T<A, B>(A, B);
+type X = HashMap<
+    String,
+    u8,
+>;
+type Y = HashMap<String, u8>;
+";
+
+#[test]
+fn test_generics_trailing_comma_heuristic() {
+    test(
+        &Config::default(),
+        GENERIC_LISTS,
+        "
+struct S<A, B>(A, B);
+struct T<A, B>(A, B);
+type X = HashMap<String, u8>;
+type Y = HashMap<String, u8>;
+",
+    );
+    test(
+        &Config::default().generics_trailing_comma_heuristic(true),
+        GENERIC_LISTS,
+        GENERIC_LISTS,
+    );
+}
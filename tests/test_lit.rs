@@ -0,0 +1,119 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::{Case, Config, QuoteStyle};
+
+#[test]
+fn test_raw_string_in_macro_and_attr() {
+    test(
+        &Config::default(),
+        r###"
+#[attr(value = r#"a "quoted" value"#)]
+fn f() {
+    println!(r#"raw "string" with {} placeholder"#, x);
+    let s = r##"contains "# sequence"##;
+}
+"###,
+        r###"
+#[attr(value = r#"a "quoted" value"#)]
+fn f() {
+    println!(r#"raw "string" with {} placeholder"#, x);
+    let s = r##"contains "# sequence"##;
+}
+"###,
+    );
+}
+
+const STRINGS: &str = r##"
+const A: &str = r"C:\dir";
+const B: &str = "C:\\dir";
+const C: &str = "plain";
+const D: &str = "has \"quote\"";
+const E: &str = r#"has "quote""#;
+const F: &str = "tab\there";
+"##;
+
+#[test]
+fn test_quote_style() {
+    test(&Config::default(), STRINGS, STRINGS);
+    test(
+        &Config::default().quote_style(QuoteStyle::PreferRaw),
+        STRINGS,
+        r##"
+const A: &str = r"C:\dir";
+const B: &str = r"C:\dir";
+const C: &str = "plain";
+const D: &str = r#"has "quote""#;
+const E: &str = r#"has "quote""#;
+const F: &str = "tab\there";
+"##,
+    );
+    test(
+        &Config::default().quote_style(QuoteStyle::PreferEscaped),
+        STRINGS,
+        r#"
+const A: &str = "C:\\dir";
+const B: &str = "C:\\dir";
+const C: &str = "plain";
+const D: &str = "has \"quote\"";
+const E: &str = "has \"quote\"";
+const F: &str = "tab\there";
+"#,
+    );
+}
+
+const NUMBERS: &str = "
+const A: u32 = 0xDEADBEEF;
+const B: u32 = 0xdead_beefu32;
+const C: f64 = 1.5E10;
+const D: f64 = 2e-3f64;
+";
+
+#[test]
+fn test_hex_case() {
+    test(&Config::default(), NUMBERS, NUMBERS);
+    test(
+        &Config::default().hex_case(Case::Upper),
+        NUMBERS,
+        "
+const A: u32 = 0xDEADBEEF;
+const B: u32 = 0xDEAD_BEEFu32;
+const C: f64 = 1.5E10;
+const D: f64 = 2e-3f64;
+",
+    );
+    test(
+        &Config::default().hex_case(Case::Lower),
+        NUMBERS,
+        "
+const A: u32 = 0xdeadbeef;
+const B: u32 = 0xdead_beefu32;
+const C: f64 = 1.5E10;
+const D: f64 = 2e-3f64;
+",
+    );
+}
+
+#[test]
+fn test_exponent_case() {
+    test(
+        &Config::default().exponent_case(Case::Upper),
+        NUMBERS,
+        "
+const A: u32 = 0xDEADBEEF;
+const B: u32 = 0xdead_beefu32;
+const C: f64 = 1.5E10;
+const D: f64 = 2E-3f64;
+",
+    );
+    test(
+        &Config::default().exponent_case(Case::Lower),
+        NUMBERS,
+        "
+const A: u32 = 0xDEADBEEF;
+const B: u32 = 0xdead_beefu32;
+const C: f64 = 1.5e10;
+const D: f64 = 2e-3f64;
+",
+    );
+}
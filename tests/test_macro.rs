@@ -0,0 +1,220 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::Config;
+
+const MACRO_RULES: &str = "
+macro_rules! m {
+    ($name:ident, $first_expression:expr, $second_expression:expr, $($rest:tt),* $(,)?) => {
+        println!(\"{}\" , $name)
+    };
+}
+";
+
+#[test]
+fn test_matchers_off() {
+    test(
+        &Config::default(),
+        MACRO_RULES,
+        r#"
+macro_rules! m {
+    (
+        $name:ident, $first_expression:expr, $second_expression:expr, $($rest:tt),* $(,)?
+    ) => {
+        println!("{}", $name)
+    };
+}
+"#,
+    );
+}
+
+#[test]
+fn test_matchers_on() {
+    test(
+        &Config::new().format_macro_matchers(true),
+        MACRO_RULES,
+        r#"
+macro_rules! m {
+    (
+        $name:ident,
+        $first_expression:expr,
+        $second_expression:expr,
+        $($rest:tt),* $(,)?
+    ) => {
+        println!("{}", $name)
+    };
+}
+"#,
+    );
+}
+
+const VEC: &str = "
+fn f() {
+    let v = vec![Foo { a: 1 }, Foo { a: 2 }, Foo { a: 3 }, Foo { a: 4 }, Foo { a: 5 }, Foo { a: 6 }];
+}
+";
+
+#[test]
+fn test_bodies_off() {
+    test(
+        &Config::default(),
+        VEC,
+        r#"
+fn f() {
+    let v = vec![
+        Foo { a : 1 }, Foo { a : 2 }, Foo { a : 3 }, Foo { a : 4 }, Foo { a : 5 }, Foo {
+        a : 6 }
+    ];
+}
+"#,
+    );
+}
+
+#[cfg(feature = "verbatim")]
+#[test]
+fn test_bodies_on() {
+    test(
+        &Config::new().format_macro_bodies(true),
+        VEC,
+        r#"
+fn f() {
+    let v = vec![
+        Foo { a: 1 },
+        Foo { a: 2 },
+        Foo { a: 3 },
+        Foo { a: 4 },
+        Foo { a: 5 },
+        Foo { a: 6 },
+    ];
+}
+"#,
+    );
+}
+
+#[test]
+fn test_statement_and_expression_position() {
+    test(
+        &Config::default(),
+        r#"
+fn f() -> Vec<u8> {
+    println!("{}", x);
+    foo! { a b }
+    foo! {}
+    let v = vec![1, 2];
+    bar!(x)
+}
+fn g() -> Vec<u8> { vec![1, 2, 3] }
+"#,
+        r#"
+fn f() -> Vec<u8> {
+    println!("{}", x);
+    foo! {
+        a b
+    }
+    foo! {}
+    let v = vec![1, 2];
+    bar!(x)
+}
+fn g() -> Vec<u8> {
+    vec![1, 2, 3]
+}
+"#,
+    );
+}
+
+#[test]
+fn test_register_macro() {
+    // Puts each SQL clause on its own line.
+    let config = Config::default().register_macro("sql", |tokens| {
+        tokens
+            .to_string()
+            .replace(" ,", ",")
+            .replace(" FROM ", "\nFROM ")
+            .replace(" WHERE ", "\nWHERE ")
+    });
+    test(
+        &config,
+        "
+fn f() {
+    let rows = sql!(SELECT id, name FROM users WHERE id = 1);
+    let rows = db::sql!(SELECT id FROM users);
+    let other = other!(SELECT id FROM users);
+}
+",
+        "
+fn f() {
+    let rows = sql!(
+        SELECT id, name
+        FROM users
+        WHERE id = 1
+    );
+    let rows = db::sql!(
+        SELECT id
+        FROM users
+    );
+    let other = other!(SELECT id FROM users);
+}
+",
+    );
+}
+
+#[test]
+fn test_braced_statement_macros() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    thread_local! {}
+    m! { b };
+    let x = 1;
+    m! { c };
+}
+fn g() -> TokenStream {
+    quote! { struct S; }
+}
+",
+        "
+fn f() {
+    thread_local! {}
+    m! {
+        b
+    }
+    let x = 1;
+    m! {
+        c
+    };
+}
+fn g() -> TokenStream {
+    quote! {
+        struct S;
+    }
+}
+",
+    );
+}
+
+#[test]
+fn test_macro_stmt_without_semi() {
+    // A syntax tree built by hand may leave out the semicolon of a macro
+    // statement that the parser would have required.
+    let mut file = syn::parse_file("fn f() { a!(1); b![2]; c! {} d!(3) }").unwrap();
+    let item = match &mut file.items[0] {
+        syn::Item::Fn(item) => item,
+        _ => unreachable!(),
+    };
+    for stmt in &mut item.block.stmts {
+        if let syn::Stmt::Macro(stmt) = stmt {
+            stmt.semi_token = None;
+        }
+    }
+    let expected = "
+fn f() {
+    a!(1);
+    b![2];
+    c! {}
+    d!(3)
+}
+";
+    let formatted = prettyplease::unparse(&file);
+    assert_eq!(formatted, expected.strip_prefix('\n').unwrap());
+}
@@ -0,0 +1,105 @@
+use prettyplease::Config;
+use proc_macro2::TokenStream;
+use std::mem;
+use syn::{Expr, ExprParen, File, Item, Pat, PatParen, Stmt, Type, TypeParen};
+
+fn nested(depth: usize, mut expr: Expr, mut pat: Pat, mut ty: Type) -> (Expr, Pat, Type) {
+    for _ in 0..depth {
+        expr = Expr::Paren(ExprParen {
+            attrs: Vec::new(),
+            paren_token: Default::default(),
+            expr: Box::new(expr),
+        });
+        pat = Pat::Paren(PatParen {
+            attrs: Vec::new(),
+            paren_token: Default::default(),
+            pat: Box::new(pat),
+        });
+        ty = Type::Paren(TypeParen {
+            paren_token: Default::default(),
+            elem: Box::new(ty),
+        });
+    }
+    (expr, pat, ty)
+}
+
+// Builds `fn f() { let ((x)): ((T)) = ((x)); }` with `depth` parentheses
+// around each of the pattern, type and expression.
+fn nested_file(depth: usize) -> File {
+    let mut file = syn::parse_file("fn f() { let x: T = x; }").unwrap();
+    let item = match &mut file.items[0] {
+        Item::Fn(item) => item,
+        _ => unreachable!(),
+    };
+    let local = match &mut item.block.stmts[0] {
+        Stmt::Local(local) => local,
+        _ => unreachable!(),
+    };
+    let pat_type = match &mut local.pat {
+        Pat::Type(pat_type) => pat_type,
+        _ => unreachable!(),
+    };
+    let init = local.init.as_mut().unwrap();
+    let (expr, pat, ty) = nested(
+        depth,
+        mem::replace(&mut *init.expr, Expr::Verbatim(TokenStream::new())),
+        mem::replace(&mut *pat_type.pat, Pat::Verbatim(TokenStream::new())),
+        mem::replace(&mut *pat_type.ty, Type::Verbatim(TokenStream::new())),
+    );
+    init.expr = Box::new(expr);
+    pat_type.pat = Box::new(pat);
+    pat_type.ty = Box::new(ty);
+    file
+}
+
+#[test]
+fn test_long_binary_chain() {
+    let terms = vec!["a"; 1200].join(" + ");
+    let src = format!("fn f() {{ {}; }}", terms);
+    let file = syn::parse_file(&src).unwrap();
+    let (formatted, _changed) = prettyplease::unparse_checked(&file, &src);
+    assert_eq!(formatted.matches(" + a").count(), 1199);
+    assert_eq!(formatted, prettyplease::unparse(&file));
+}
+
+#[test]
+fn test_deep_nesting_within_limit() {
+    let file = nested_file(990);
+    let (formatted, changed) = prettyplease::try_unparse_checked(&file, "").unwrap();
+    assert!(changed);
+    // 990 parentheses each around the pattern, type and expression, plus the
+    // ones in `fn f()`.
+    assert_eq!(formatted.matches('(').count(), 2971);
+    assert_eq!(formatted.matches(')').count(), 2971);
+}
+
+#[test]
+fn test_deep_nesting_is_error() {
+    let file = nested_file(1200);
+    let err = prettyplease::try_unparse_checked(&file, "").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "syntax tree is nested more than 1000 levels deep",
+    );
+}
+
+#[test]
+fn test_deep_nesting_fallback() {
+    let file = nested_file(1200);
+    let formatted = prettyplease::unparse(&file);
+    let placeholder = "compile_error!(\"syntax tree is nested more than 1000 levels deep\")";
+    assert_eq!(formatted.matches(placeholder).count(), 3);
+    // 1000 parentheses around the expression, and 999 each around the pattern
+    // and type inside of the `x: T` pattern, plus the ones in `fn f()` and in
+    // the three placeholders.
+    assert_eq!(formatted.matches('(').count(), 3002);
+    assert_eq!(formatted.matches(')').count(), 3002);
+
+    let mut out = String::new();
+    let err = prettyplease::unparse_to_sink(&file, &Config::default(), &mut out).unwrap_err();
+    assert_eq!(
+        err,
+        prettyplease::try_unparse_checked(&file, "").unwrap_err()
+    );
+    assert_eq!(out, formatted);
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "verbatim")]
+
+#[test]
+fn test_is_formatted() {
+    assert!(prettyplease::is_formatted("fn main() {\n    println!(\"hello\");\n}\n").unwrap());
+    assert!(!prettyplease::is_formatted("fn main() { println!(\"hello\"); }\n").unwrap());
+    assert!(!prettyplease::is_formatted("fn main() {\n    println!(\"hello\");\n}").unwrap());
+    assert!(prettyplease::is_formatted("fn main() {").is_err());
+}
+
+#[test]
+fn test_format_fragment() {
+    let format = |src| prettyplease::format_fragment(src).unwrap();
+    assert_eq!(format("a+b*c"), "a + b * c\n");
+    assert_eq!(format("Vec<  u8 >"), "Vec<u8>\n");
+    assert_eq!(
+        format("fn main(){let x=1;}"),
+        "fn main() {\n    let x = 1;\n}\n",
+    );
+    assert_eq!(format("let x = 1; x+1"), "let x = 1;\nx + 1\n");
+    assert_eq!(format("Some(  x )|None"), "Some(x) | None\n");
+    assert!(prettyplease::format_fragment("fn main() {").is_err());
+}
+
+#[test]
+fn test_format_fragment_struct_rest() {
+    let format = |src| prettyplease::format_fragment(src).unwrap();
+    assert_eq!(format("Foo { a, .. }"), "Foo { a, .. }\n");
+    assert_eq!(format("Foo { a, ..base }"), "Foo { a, ..base }\n");
+    assert_eq!(
+        format("fn f() { Foo { a, .. } = x; }"),
+        "fn f() {\n    Foo { a, .. } = x;\n}\n",
+    );
+}
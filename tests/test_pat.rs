@@ -0,0 +1,82 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::Config;
+
+#[test]
+fn test_struct_pattern() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    let SomeLongStructName { shorthand_field, renamed_field: new_binding_name, another_field, .. } = value;
+    let Point { x, y: renamed, .. } = p;
+}
+",
+        "
+fn f() {
+    let SomeLongStructName {
+        shorthand_field,
+        renamed_field: new_binding_name,
+        another_field,
+        ..
+    } = value;
+    let Point { x, y: renamed, .. } = p;
+}
+",
+    );
+}
+
+#[test]
+fn test_tuple_rest() {
+    let src = "
+fn f() {
+    let Foo(a, .., b) = x;
+    let (a, .., b) = x;
+    let Foo(a, b, ..) = x;
+    let (a, b, ..) = x;
+    let (..) = x;
+    let (a,) = x;
+}
+";
+    test(&Config::default(), src, src);
+    test(
+        &Config::default(),
+        "
+fn f() {
+    let VeryLongTupleStructName(first_binding_name, second_binding_name, .., last_binding_name) = value;
+}
+",
+        "
+fn f() {
+    let VeryLongTupleStructName(
+        first_binding_name,
+        second_binding_name,
+        ..,
+        last_binding_name,
+    ) = value;
+}
+",
+    );
+}
+
+#[test]
+fn test_or_patterns() {
+    test(
+        &Config::default(),
+        "
+fn f((Ok(x) | Err(x)): Result<u8, u8>) {
+    let (Ok(v) | Err(v)) = result;
+    let (SomeVeryLongEnumName::FirstVariant(value) | SomeVeryLongEnumName::SecondVariant(value) | SomeVeryLongEnumName::ThirdVariant(value)) = input;
+}
+",
+        "
+fn f((Ok(x) | Err(x)): Result<u8, u8>) {
+    let (Ok(v) | Err(v)) = result;
+    let (SomeVeryLongEnumName::FirstVariant(value)
+    | SomeVeryLongEnumName::SecondVariant(value)
+    | SomeVeryLongEnumName::ThirdVariant(value)) = input;
+}
+",
+    );
+}
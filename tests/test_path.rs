@@ -0,0 +1,108 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::{AbsolutePathStyle, Config, Edition};
+
+const PATHS: &str = "
+use std::mem;
+use ::core::fmt;
+fn f() {
+    std::mem::swap(a, b);
+    ::alloc::vec::Vec::new();
+    other::thing();
+    ::other::thing();
+}
+";
+
+#[test]
+fn test_absolute_paths_preserve() {
+    test(
+        &Config::default(),
+        PATHS,
+        "
+use std::mem;
+use ::core::fmt;
+fn f() {
+    std::mem::swap(a, b);
+    ::alloc::vec::Vec::new();
+    other::thing();
+    ::other::thing();
+}
+",
+    );
+}
+
+#[test]
+fn test_absolute_paths_global() {
+    test(
+        &Config::default().absolute_paths(AbsolutePathStyle::Global),
+        PATHS,
+        "
+use ::std::mem;
+use ::core::fmt;
+fn f() {
+    ::std::mem::swap(a, b);
+    ::alloc::vec::Vec::new();
+    other::thing();
+    ::other::thing();
+}
+",
+    );
+}
+
+#[test]
+fn test_absolute_paths_relative() {
+    test(
+        &Config::default().absolute_paths(AbsolutePathStyle::Relative),
+        PATHS,
+        "
+use std::mem;
+use core::fmt;
+fn f() {
+    std::mem::swap(a, b);
+    alloc::vec::Vec::new();
+    other::thing();
+    ::other::thing();
+}
+",
+    );
+    test(
+        &Config::default()
+            .absolute_paths(AbsolutePathStyle::Relative)
+            .edition(Edition::E2015),
+        PATHS,
+        "
+use std::mem;
+use ::core::fmt;
+fn f() {
+    std::mem::swap(a, b);
+    ::alloc::vec::Vec::new();
+    other::thing();
+    ::other::thing();
+}
+",
+    );
+}
+
+#[test]
+fn test_qself_without_trait() {
+    test(
+        &Config::default(),
+        "
+fn f() {
+    let a = <SomeLongType>::CONST;
+    let b = <[T; N]>::len();
+    let c = <Vec<HashMap<String, BTreeMap<u64, Option<SomeVeryLongTypeName>>>>>::with_capacity(capacity_argument);
+}
+",
+        "
+fn f() {
+    let a = <SomeLongType>::CONST;
+    let b = <[T; N]>::len();
+    let c = <Vec<
+        HashMap<String, BTreeMap<u64, Option<SomeVeryLongTypeName>>>,
+    >>::with_capacity(capacity_argument);
+}
+",
+    );
+}
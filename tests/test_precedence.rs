@@ -0,0 +1,193 @@
+use proc_macro2::TokenStream;
+use std::mem;
+use syn::{Expr, ExprGroup, Item, Stmt};
+
+// Formats `expr` as the only statement of a function, after removing every
+// parenthesis from it so that the printer has to put back the ones that are
+// needed.
+fn unparse_without_parens(src: &str) -> String {
+    unparse_stmt(src, strip_parens)
+}
+
+// Same, but every parenthesized expression becomes an Expr::Group, as in the
+// tokens of a macro_rules `$e:expr` fragment.
+fn unparse_with_groups(src: &str) -> String {
+    unparse_stmt(src, parens_to_groups)
+}
+
+fn unparse_stmt(src: &str, f: fn(&mut Expr)) -> String {
+    let mut file = syn::parse_file(&format!("fn f() {{ {}; }}", src)).unwrap();
+    let item = match &mut file.items[0] {
+        Item::Fn(item) => item,
+        _ => unreachable!(),
+    };
+    match &mut item.block.stmts[0] {
+        Stmt::Expr(expr, _) => f(expr),
+        _ => unreachable!(),
+    }
+    let formatted = prettyplease::unparse(&file);
+    let stmt = formatted
+        .strip_prefix("fn f() {\n    ")
+        .and_then(|rest| rest.strip_suffix(";\n}\n"))
+        .unwrap();
+    stmt.to_owned()
+}
+
+fn strip_parens(expr: &mut Expr) {
+    if let Expr::Paren(paren) = expr {
+        let inner = mem::replace(&mut *paren.expr, Expr::Verbatim(TokenStream::new()));
+        *expr = inner;
+        strip_parens(expr);
+        return;
+    }
+    for_each_operand(expr, strip_parens);
+}
+
+fn parens_to_groups(expr: &mut Expr) {
+    if let Expr::Paren(paren) = expr {
+        let mut inner = mem::replace(&mut *paren.expr, Expr::Verbatim(TokenStream::new()));
+        parens_to_groups(&mut inner);
+        *expr = Expr::Group(ExprGroup {
+            attrs: Vec::new(),
+            group_token: Default::default(),
+            expr: Box::new(inner),
+        });
+        return;
+    }
+    for_each_operand(expr, parens_to_groups);
+}
+
+fn for_each_operand(expr: &mut Expr, f: fn(&mut Expr)) {
+    match expr {
+        Expr::Await(expr) => f(&mut expr.base),
+        Expr::Binary(expr) => {
+            f(&mut expr.left);
+            f(&mut expr.right);
+        }
+        Expr::Call(expr) => f(&mut expr.func),
+        Expr::Cast(expr) => f(&mut expr.expr),
+        Expr::Field(expr) => f(&mut expr.base),
+        Expr::Index(expr) => {
+            f(&mut expr.expr);
+            f(&mut expr.index);
+        }
+        Expr::MethodCall(expr) => f(&mut expr.receiver),
+        Expr::Range(expr) => {
+            if let Some(start) = &mut expr.start {
+                f(start);
+            }
+            if let Some(end) = &mut expr.end {
+                f(end);
+            }
+        }
+        Expr::Reference(expr) => f(&mut expr.expr),
+        Expr::Try(expr) => f(&mut expr.expr),
+        Expr::Unary(expr) => f(&mut expr.expr),
+        _ => {}
+    }
+}
+
+#[test]
+fn test_parsed_parens_kept() {
+    let file = syn::parse_file("fn f() { (a & b) == c; }").unwrap();
+    let formatted = prettyplease::unparse(&file);
+    assert_eq!(formatted, "fn f() {\n    (a & b) == c;\n}\n");
+}
+
+#[test]
+fn test_binary() {
+    // `&` binds tighter than `==` in Rust, unlike in C.
+    assert_eq!(unparse_without_parens("(a & b) == c"), "a & b == c");
+    assert_eq!(unparse_without_parens("a & (b == c)"), "a & (b == c)");
+    assert_eq!(unparse_without_parens("(a == b) == c"), "(a == b) == c");
+    assert_eq!(unparse_without_parens("a < (b < c)"), "a < (b < c)");
+    assert_eq!(
+        unparse_without_parens("(a | b) ^ (c & d)"),
+        "(a | b) ^ c & d"
+    );
+    assert_eq!(unparse_without_parens("a - (b - c)"), "a - (b - c)");
+    assert_eq!(unparse_without_parens("(a - b) - c"), "a - b - c");
+    assert_eq!(
+        unparse_without_parens("(a as usize) < b"),
+        "(a as usize) < b"
+    );
+}
+
+#[test]
+fn test_group() {
+    assert_eq!(unparse_with_groups("(a + b) * c"), "(a + b) * c");
+    assert_eq!(unparse_with_groups("(a * b) + c"), "a * b + c");
+    assert_eq!(unparse_with_groups("-(a + b)"), "-(a + b)");
+    assert_eq!(unparse_with_groups("((a))"), "a");
+}
+
+#[test]
+fn test_statement_position() {
+    assert_eq!(unparse_without_parens("({ a } - 1)"), "({ a } - 1)");
+    assert_eq!(
+        unparse_without_parens("(match x {} as u8)"),
+        "(match x {} as u8)"
+    );
+    assert_eq!(unparse_without_parens("({ a }).b()"), "{ a }.b()");
+    assert_eq!(
+        unparse_without_parens("(Foo { x: 1 }).bar()"),
+        "Foo { x: 1 }.bar()"
+    );
+    assert_eq!(unparse_without_parens("(Foo { x: 1 })"), "Foo { x: 1 }");
+}
+
+#[test]
+fn test_unary_operand() {
+    assert_eq!(unparse_without_parens("-(a + b)"), "-(a + b)");
+    assert_eq!(unparse_without_parens("!(a || b)"), "!(a || b)");
+    assert_eq!(unparse_without_parens("&(x as T)"), "&(x as T)");
+    assert_eq!(unparse_without_parens("*(a.b)"), "*a.b");
+    assert_eq!(unparse_without_parens("(-a) + b"), "-a + b");
+    assert_eq!(unparse_with_groups("!(a || b)"), "!(a || b)");
+}
+
+#[test]
+fn test_match_scrutinee() {
+    fn strip_scrutinee_parens(expr: &mut Expr) {
+        if let Expr::Match(expr) = expr {
+            strip_parens(&mut expr.expr);
+        }
+    }
+    assert_eq!(
+        unparse_stmt("match (a, b) {}", strip_scrutinee_parens),
+        "match (a, b) {}",
+    );
+    assert_eq!(
+        unparse_stmt("match (Foo { x }) {}", strip_scrutinee_parens),
+        "match (Foo { x }) {}",
+    );
+    assert_eq!(
+        unparse_stmt("match (Foo { x }.kind()) {}", strip_scrutinee_parens),
+        "match (Foo { x }.kind()) {}",
+    );
+}
+
+#[test]
+fn test_range_index() {
+    assert_eq!(unparse_without_parens("&arr[(a..b)]"), "&arr[a..b]");
+    assert_eq!(unparse_without_parens("&arr[..]"), "&arr[..]");
+    assert_eq!(unparse_without_parens("&arr[(a..=b)]"), "&arr[a..=b]");
+    assert_eq!(unparse_without_parens("arr[((i + 1)..)]"), "arr[i + 1..]");
+}
+
+#[test]
+fn test_postfix_operand() {
+    assert_eq!(unparse_without_parens("(a..b).len()"), "(a..b).len()");
+    assert_eq!(unparse_without_parens("(a..b).start"), "(a..b).start");
+    assert_eq!(unparse_without_parens("(a + b)[0]"), "(a + b)[0]");
+    assert_eq!(unparse_without_parens("(a as u8).len()"), "(a as u8).len()");
+    assert_eq!(unparse_without_parens("(-a).abs()"), "(-a).abs()");
+    assert_eq!(unparse_without_parens("(a..b)?"), "(a..b)?");
+    assert_eq!(unparse_without_parens("(a + b).await"), "(a + b).await");
+    assert_eq!(unparse_without_parens("(a + b)(c)"), "(a + b)(c)");
+    assert_eq!(unparse_without_parens("(a = b).c()"), "(a = b).c()");
+    assert_eq!(unparse_without_parens("(|x| x).c()"), "(|x| x).c()");
+    assert_eq!(unparse_without_parens("((a..b) as T)"), "(a..b) as T");
+    assert_eq!(unparse_without_parens("(a.b).c()"), "a.b.c()");
+    assert_eq!(unparse_without_parens("(f(x))?"), "f(x)?");
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::{Config, Punctuation};
+
+const SRC: &str = "
+fn f(x: u8) -> Vec<u8> { g(x); h::<T>(x) }
+trait T { fn m(&self); }
+";
+
+#[test]
+fn test_default() {
+    test(
+        &Config::default(),
+        SRC,
+        "
+fn f(x: u8) -> Vec<u8> {
+    g(x);
+    h::<T>(x)
+}
+trait T {
+    fn m(&self);
+}
+",
+    );
+}
+
+#[test]
+fn test_space_before_fn_paren() {
+    let mut punctuation = Punctuation::default();
+    punctuation.space_before_fn_paren = true;
+    test(
+        &Config::default().punctuation(punctuation),
+        SRC,
+        "
+fn f (x: u8) -> Vec<u8> {
+    g(x);
+    h::<T>(x)
+}
+trait T {
+    fn m (&self);
+}
+",
+    );
+}
+
+#[test]
+fn test_space_inside_angle_brackets() {
+    let mut punctuation = Punctuation::default();
+    punctuation.space_inside_angle_brackets = true;
+    test(
+        &Config::default().punctuation(punctuation),
+        SRC,
+        "
+fn f(x: u8) -> Vec< u8 > {
+    g(x);
+    h::< T >(x)
+}
+trait T {
+    fn m(&self);
+}
+",
+    );
+}
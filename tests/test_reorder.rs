@@ -0,0 +1,124 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::Config;
+
+#[test]
+fn test_macros_are_fixed_points() {
+    test(
+        &Config::new().reorder_modules(true),
+        "
+mod m {
+    fn b() {}
+    fn a() {}
+    #[macro_use]
+    mod macros;
+    fn d() {}
+    fn c() {}
+    macro_rules! x {
+        () => {};
+    }
+    struct Z;
+    use y;
+}
+",
+        r#"
+mod m {
+    fn a() {}
+    fn b() {}
+    #[macro_use]
+    mod macros;
+    fn c() {}
+    fn d() {}
+    macro_rules! x {
+        () => {};
+    }
+    use y;
+    struct Z;
+}
+"#,
+    );
+}
+
+#[test]
+fn test_reorder_impl_items() {
+    let src = "
+impl S {
+    fn a() {}
+    const X: u8 = 1;
+    type T = u8;
+    fn b() {}
+    #[cfg(x)]
+    const Y: u8 = 2;
+    type U = u16;
+    const Z: u8 = 3;
+}
+";
+    test(&Config::default(), src, src);
+    // Members are not moved across the #[cfg] item.
+    test(
+        &Config::default().reorder_impl_items(true),
+        src,
+        "
+impl S {
+    const X: u8 = 1;
+    type T = u8;
+    fn a() {}
+    fn b() {}
+    #[cfg(x)]
+    const Y: u8 = 2;
+    const Z: u8 = 3;
+    type U = u16;
+}
+",
+    );
+}
+
+const RE_EXPORTS: &str = "
+mod m {
+    mod z {}
+    use std::io;
+    pub use crate::b::B;
+    fn f() {}
+    use std::fmt;
+    pub use crate::a::*;
+    mod y {}
+}
+";
+
+#[test]
+fn test_reorder_pub_use() {
+    // Re-exports stay in place and nothing is moved past them.
+    test(
+        &Config::default().reorder_modules(true),
+        RE_EXPORTS,
+        "
+mod m {
+    use std::io;
+    mod z {}
+    pub use crate::b::B;
+    use std::fmt;
+    fn f() {}
+    pub use crate::a::*;
+    mod y {}
+}
+",
+    );
+    test(
+        &Config::default()
+            .reorder_modules(true)
+            .reorder_pub_use(true),
+        RE_EXPORTS,
+        "
+mod m {
+    use std::io;
+    pub use crate::b::B;
+    use std::fmt;
+    pub use crate::a::*;
+    fn f() {}
+    mod y {}
+    mod z {}
+}
+",
+    );
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::Config;
+
+#[test]
+fn test_closure() {
+    test(
+        &Config::default(),
+        "fn main() { let f = |x| -> impl Iterator<Item = SomeLongTypeName> + Send + Sync + AnotherTraitName + More { x }; }",
+        r#"
+fn main() {
+    let f = |x| -> impl Iterator<Item = SomeLongTypeName> + Send + Sync
+        + AnotherTraitName + More
+    { x };
+}
+"#,
+    );
+}
+
+#[test]
+fn test_short_closure() {
+    test(
+        &Config::default(),
+        "fn main() { let f = |x| -> impl Trait + AnotherTrait { x }; }",
+        r#"
+fn main() {
+    let f = |x| -> impl Trait + AnotherTrait { x };
+}
+"#,
+    );
+}
+
+#[test]
+fn test_fn() {
+    test(
+        &Config::default(),
+        "fn inner(a: u32, b: u32) -> impl Iterator<Item = SomeLongTypeName> + Send + Sync + AnotherTraitName + More { a }",
+        r#"
+fn inner(a: u32, b: u32) -> impl Iterator<Item = SomeLongTypeName> + Send + Sync
+    + AnotherTraitName + More
+{
+    a
+}
+"#,
+    );
+    test(
+        &Config::default(),
+        "fn inner() -> impl Iterator<Item = SomeLongTypeName> + Send + Sync + AnotherTraitName + More {}",
+        r#"
+fn inner() -> impl Iterator<Item = SomeLongTypeName> + Send + Sync + AnotherTraitName
+    + More
+{}
+"#,
+    );
+}
+
+#[test]
+fn test_short_fn() {
+    test(
+        &Config::default(),
+        "fn inner() -> impl Trait + AnotherTrait {}",
+        r#"
+fn inner() -> impl Trait + AnotherTrait {}
+"#,
+    );
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::Config;
+
+#[test]
+fn test_unit_block_statements() {
+    test(
+        &Config::default(),
+        "fn f() { { g(); }; unsafe { g(); }; match x { _ => {} }; h(); }",
+        r#"
+fn f() {
+    {
+        g();
+    }
+    unsafe {
+        g();
+    }
+    match x {
+        _ => {}
+    }
+    h();
+}
+"#,
+    );
+}
+
+#[test]
+fn test_value_block_statements() {
+    test(
+        &Config::default(),
+        "fn f() { if a { 1 } else { 2 }; match x { _ => 1 }; h(); }",
+        r#"
+fn f() {
+    if a { 1 } else { 2 };
+    match x {
+        _ => 1,
+    };
+    h();
+}
+"#,
+    );
+}
+
+#[test]
+fn test_tail_statement_keeps_semicolon() {
+    // Without the semicolon, the temporary borrow in the scrutinee would
+    // outlive `x` on editions before 2024.
+    test(
+        &Config::default(),
+        "fn f(x: &RefCell<T>) { match *x.borrow() { _ => {} }; }",
+        r#"
+fn f(x: &RefCell<T>) {
+    match *x.borrow() {
+        _ => {}
+    };
+}
+"#,
+    );
+    test(
+        &Config::default(),
+        "fn f() { g(); unsafe { h(); }; }",
+        r#"
+fn f() {
+    g();
+    unsafe {
+        h();
+    };
+}
+"#,
+    );
+}
+
+#[test]
+fn test_empty_statements() {
+    let src = "
+fn f() {
+    a();
+    ;
+    b();
+    ;
+    ;
+}
+";
+    test(
+        &Config::default(),
+        src,
+        "
+fn f() {
+    a();
+    b();
+}
+",
+    );
+    test(&Config::default().strip_empty_statements(false), src, src);
+}
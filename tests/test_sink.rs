@@ -0,0 +1,41 @@
+use prettyplease::{Config, Sink, WriteSink};
+
+const SRC: &str = "fn main() { let x = 1; let y = vec![x, x]; }";
+
+#[derive(Default)]
+struct CountingSink {
+    bytes: usize,
+    lines: usize,
+    pieces: usize,
+}
+
+impl Sink for CountingSink {
+    fn push_str(&mut self, string: &str) {
+        self.bytes += string.len();
+        self.lines += string.matches('\n').count();
+        self.pieces += 1;
+    }
+}
+
+#[test]
+fn test_counting_sink() {
+    let file = syn::parse_file(SRC).unwrap();
+    let expected = prettyplease::unparse(&file);
+    let mut sink = CountingSink::default();
+    prettyplease::unparse_to_sink(&file, &Config::default(), &mut sink).unwrap();
+    assert_eq!(sink.bytes, expected.len());
+    assert_eq!(sink.lines, expected.lines().count());
+    assert!(sink.pieces > 1);
+}
+
+#[test]
+fn test_write_sink() {
+    let file = syn::parse_file(SRC).unwrap();
+    let mut sink = WriteSink::new(Vec::new());
+    prettyplease::unparse_to_sink(&file, &Config::default(), &mut sink).unwrap();
+    let written = sink.finish().unwrap();
+    assert_eq!(
+        String::from_utf8(written).unwrap(),
+        prettyplease::unparse(&file)
+    );
+}
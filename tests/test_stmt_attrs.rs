@@ -0,0 +1,85 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::Config;
+
+#[test]
+fn test_assign_targets() {
+    test(
+        &Config::default(),
+        "fn f() { #[cfg(a)] a = b; #[allow(unused)] x += 1; #[attr] x.0 = 5; #[attr] *p = 1; }",
+        r#"
+fn f() {
+    #[cfg(a)]
+    a = b;
+    #[allow(unused)]
+    x += 1;
+    #[attr]
+    x.0 = 5;
+    #[attr]
+    *p = 1;
+}
+"#,
+    );
+}
+
+#[test]
+fn test_other_statements() {
+    test(
+        &Config::default(),
+        "fn f() { #[a] #[b] g(x); #[a] x + y; #[a] x as u8; #[a] x }",
+        r#"
+fn f() {
+    #[a]
+    #[b]
+    g(x);
+    #[a]
+    x + y;
+    #[a]
+    x as u8;
+    #[a]
+    x
+}
+"#,
+    );
+}
+
+#[test]
+fn test_cfg_match_arm_and_block() {
+    test(
+        &Config::default(),
+        r#"
+fn f() {
+    match x { A => 1, #[cfg(feature = "b")] B => 2 }
+    #[cfg(unix)] { let y = 1; }
+}
+"#,
+        r#"
+fn f() {
+    match x {
+        A => 1,
+        #[cfg(feature = "b")]
+        B => 2,
+    }
+    #[cfg(unix)]
+    {
+        let y = 1;
+    }
+}
+"#,
+    );
+}
+
+#[test]
+fn test_attrs_stay_inline_within_expression() {
+    test(
+        &Config::default(),
+        "fn f() { g(#[a] x); y = #[b] z; }",
+        r#"
+fn f() {
+    g(#[a] x);
+    y = #[b] z;
+}
+"#,
+    );
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::{Config, Edition};
+
+const TRAIT_OBJECTS: &str = "
+fn f(x: Box<Trait + Send>, y: &(Trait + 'static), z: Box<dyn Trait>) {}
+";
+
+#[test]
+fn test_dyn_by_edition() {
+    test(
+        &Config::default().edition(Edition::E2015),
+        TRAIT_OBJECTS,
+        "
+fn f(x: Box<Trait + Send>, y: &(Trait + 'static), z: Box<dyn Trait>) {}
+",
+    );
+    for edition in [Edition::E2018, Edition::E2021, Edition::E2024] {
+        test(
+            &Config::default().edition(edition),
+            TRAIT_OBJECTS,
+            "
+fn f(x: Box<dyn Trait + Send>, y: &(dyn Trait + 'static), z: Box<dyn Trait>) {}
+",
+        );
+    }
+}
+
+#[test]
+fn test_verbatim_bound() {
+    use proc_macro2::TokenStream;
+    use syn::{GenericParam, Item, TypeParamBound};
+
+    let mut file = syn::parse_file("fn f<T: Clone + Bound>() {}").unwrap();
+    let item = match &mut file.items[0] {
+        Item::Fn(item) => item,
+        _ => unreachable!(),
+    };
+    let param = match &mut item.sig.generics.params[0] {
+        GenericParam::Type(param) => param,
+        _ => unreachable!(),
+    };
+    let tokens: TokenStream = "unknown_bound_syntax!(T)".parse().unwrap();
+    param.bounds[1] = TypeParamBound::Verbatim(tokens);
+    let formatted = prettyplease::unparse(&file);
+    assert_eq!(
+        formatted,
+        "fn f<T: Clone + unknown_bound_syntax!(T)>() {}\n"
+    );
+}
+
+#[test]
+fn test_lifetimes() {
+    let src = "
+fn f<'a, 'static_ish, 'r#gen>(x: &'a u8, y: &'r#gen u8) -> &'a u8 {
+    x
+}
+";
+    test(&Config::default(), src, src);
+}
+
+#[test]
+fn test_bare_fn_named_args() {
+    test(
+        &Config::default(),
+        r#"
+type F = fn(x: i32, y: i32) -> i32;
+type G = fn(i32, _: u8) -> i32;
+type H = unsafe extern "C" fn(fmt: *const c_char, ...) -> c_int;
+type L = fn(first_argument_name: SomeLongType, second_argument_name: AnotherLongType) -> Result<(), Error>;
+"#,
+        r#"
+type F = fn(x: i32, y: i32) -> i32;
+type G = fn(i32, _: u8) -> i32;
+type H = unsafe extern "C" fn(fmt: *const c_char, ...) -> c_int;
+type L = fn(
+    first_argument_name: SomeLongType,
+    second_argument_name: AnotherLongType,
+) -> Result<(), Error>;
+"#,
+    );
+}
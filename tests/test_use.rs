@@ -0,0 +1,67 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::Config;
+
+const USES: &str = "
+use a::{b};
+use a::{b::{c}};
+use a::{*};
+use a::{self};
+use a::{self as c};
+use a::{self, b};
+";
+
+#[test]
+fn test_groups_preserved_by_default() {
+    test(
+        &Config::default(),
+        USES,
+        r#"
+use a::{b};
+use a::{b::{c}};
+use a::{*};
+use a::{self};
+use a::{self as c};
+use a::{self, b};
+"#,
+    );
+}
+
+#[test]
+fn test_collapse_use_groups() {
+    test(
+        &Config::new().collapse_use_groups(true),
+        USES,
+        r#"
+use a::b;
+use a::b::c;
+use a::*;
+use a::{self};
+use a::{self as c};
+use a::{self, b};
+"#,
+    );
+}
+
+#[test]
+fn test_imports_not_reordered() {
+    // Blank lines are not in the syntax tree, but no import moves out of
+    // its group either.
+    test(
+        &Config::default(),
+        "
+use std::io;
+use std::fmt;
+
+use serde::Serialize;
+use anyhow::Result;
+",
+        "
+use std::io;
+use std::fmt;
+use serde::Serialize;
+use anyhow::Result;
+",
+    );
+}
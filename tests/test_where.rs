@@ -0,0 +1,154 @@
+mod common;
+
+use crate::common::test;
+use prettyplease::Config;
+
+const SRC: &str = "
+fn f<T>(t: T) where T: Clone {}
+fn g<T, U>(t: T, u: U) where T: Clone + Debug + Send + Sync + 'static, U: Iterator<Item = T> + Clone {}
+impl<T> Trait for S<T> where T: Clone {}
+struct W<T> where T: Clone { t: T }
+";
+
+#[test]
+fn test_default() {
+    test(
+        &Config::default(),
+        SRC,
+        "
+fn f<T>(t: T) where T: Clone {}
+fn g<T, U>(t: T, u: U)
+where
+    T: Clone + Debug + Send + Sync + 'static,
+    U: Iterator<Item = T> + Clone,
+{}
+impl<T> Trait for S<T> where T: Clone {}
+struct W<T> where T: Clone {
+    t: T,
+}
+",
+    );
+}
+
+#[test]
+fn test_no_inline_short_where() {
+    test(
+        &Config::default().inline_short_where(false),
+        SRC,
+        "
+fn f<T>(t: T)
+where
+    T: Clone,
+{}
+fn g<T, U>(t: T, u: U)
+where
+    T: Clone + Debug + Send + Sync + 'static,
+    U: Iterator<Item = T> + Clone,
+{}
+impl<T> Trait for S<T>
+where
+    T: Clone,
+{}
+struct W<T>
+where
+    T: Clone,
+{
+    t: T,
+}
+",
+    );
+}
+
+#[test]
+fn test_broken_signature_breaks_where() {
+    test(
+        &Config::default(),
+        "
+fn function_with_a_long_name<T>(first_argument: T, second_argument: Vec<T>, third: u8) -> Vec<T> where T: Clone { body() }
+fn shorter_function_name<T>(first_argument: T, second_argument: Vec<T>) -> Vec<T> where T: Clone { body() }
+",
+        "
+fn function_with_a_long_name<T>(
+    first_argument: T,
+    second_argument: Vec<T>,
+    third: u8,
+) -> Vec<T>
+where
+    T: Clone,
+{
+    body()
+}
+fn shorter_function_name<T>(first_argument: T, second_argument: Vec<T>) -> Vec<T>
+where
+    T: Clone,
+{
+    body()
+}
+",
+    );
+}
+
+#[test]
+fn test_empty_where() {
+    let src = "
+fn f<T>() where {}
+struct S<T> where { t: T }
+struct U<T>(T) where;
+impl<T> S<T> where {}
+trait Tr where { fn g() where; }
+type A<T> where = T;
+";
+    let expected = "
+fn f<T>() {}
+struct S<T> {
+    t: T,
+}
+struct U<T>(T);
+impl<T> S<T> {}
+trait Tr {
+    fn g();
+}
+type A<T> = T;
+";
+    test(&Config::default(), src, expected);
+    test(&Config::default().inline_short_where(false), src, expected);
+}
+
+#[test]
+fn test_predicate_kinds() {
+    test(
+        &Config::default(),
+        "
+fn f<'a, 'b, T, F>() where 'a: 'b, T: 'a + Clone, for<'c> F: Fn(&'c T) -> &'c T, for<'c> &'c T: IntoIterator<Item = &'c u8> {}
+",
+        "
+fn f<'a, 'b, T, F>()
+where
+    'a: 'b,
+    T: 'a + Clone,
+    for<'c> F: Fn(&'c T) -> &'c T,
+    for<'c> &'c T: IntoIterator<Item = &'c u8>,
+{}
+",
+    );
+}
+
+#[test]
+fn test_long_trait_bound() {
+    test(
+        &Config::default(),
+        "
+fn g<T>() where T: SomeVeryLongTraitName<'a, AnotherLongArgumentType, Item = YetAnotherLongTypeName<u8>> {}
+",
+        "
+fn g<T>()
+where
+    T: SomeVeryLongTraitName<
+        'a,
+        AnotherLongArgumentType,
+        Item = YetAnotherLongTypeName<u8>,
+    >,
+{}
+",
+    );
+}
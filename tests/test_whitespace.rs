@@ -0,0 +1,28 @@
+use prettyplease::{Config, DocStyle, EmptyBlock};
+
+// Doc comments whose lines end in spaces, and a line break right after the
+// space of an empty block.
+const SRC: &str = r#"
+/// Trailing spaces   
+/** Block doc   
+    with trailing spaces   
+*/
+#[doc = "attribute   \n with trailing spaces   "]
+trait Foo: Aaaaaaaaaaaaaaaaaaaa + Bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb + Ccccccccccccccccccccccc + Ddddddddddd {}
+"#;
+
+#[test]
+fn test_no_trailing_whitespace() {
+    let file = syn::parse_file(SRC).unwrap();
+    for config in [
+        Config::default(),
+        Config::default().empty_block_style(EmptyBlock::Spaced),
+        Config::default().doc_comment_style(DocStyle::LineComments),
+        Config::default().doc_comment_style(DocStyle::BlockComments),
+    ] {
+        let formatted = prettyplease::unparse_with_config(&file, &config);
+        for line in formatted.lines() {
+            assert_eq!(line, line.trim_end(), "{}", formatted);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use prettyplease::{Config, WidthMetric};
+
+// Widest run of padding in `f(pad, "s");` that still fits on one line.
+fn max_pad(metric: WidthMetric, s: &str) -> usize {
+    let config = Config::default().width_metric(metric);
+    (1..100)
+        .take_while(|&n| {
+            let src = format!("fn main() {{ f({}, \"{}\"); }}", "x".repeat(n), s);
+            let file = syn::parse_file(&src).unwrap();
+            let formatted = prettyplease::unparse_with_config(&file, &config);
+            formatted.lines().count() == 3
+        })
+        .last()
+        .unwrap()
+}
+
+fn width(metric: WidthMetric, s: &str) -> usize {
+    max_pad(metric, "") - max_pad(metric, s)
+}
+
+#[test]
+fn test_ascii() {
+    for metric in [
+        WidthMetric::Bytes,
+        WidthMetric::Chars,
+        WidthMetric::UnicodeWidth,
+    ] {
+        assert_eq!(width(metric, "abc"), 3);
+    }
+}
+
+#[test]
+fn test_bytes() {
+    assert_eq!(width(WidthMetric::Bytes, "ö"), 2);
+    assert_eq!(width(WidthMetric::Bytes, "中文"), 6);
+    assert_eq!(width(WidthMetric::Bytes, "🦀"), 4);
+}
+
+#[test]
+fn test_chars() {
+    assert_eq!(width(WidthMetric::Chars, "ö"), 1);
+    assert_eq!(width(WidthMetric::Chars, "中文"), 2);
+    assert_eq!(width(WidthMetric::Chars, "e\u{301}"), 2);
+    assert_eq!(width(WidthMetric::Chars, "🦀"), 1);
+}
+
+#[test]
+fn test_unicode_width() {
+    let metric = WidthMetric::UnicodeWidth;
+    // Latin, Greek, Cyrillic.
+    assert_eq!(width(metric, "öλж"), 3);
+    // Combining marks, zero width space, variation selector.
+    assert_eq!(width(metric, "e\u{301}\u{1DC0}\u{20D7}"), 1);
+    assert_eq!(width(metric, "a\u{200B}b"), 2);
+    assert_eq!(width(metric, "\u{2764}\u{FE0F}"), 1);
+    // Hangul jamo and syllables.
+    assert_eq!(width(metric, "\u{1100}한국어"), 8);
+    // CJK ideographs, including extension A and B.
+    assert_eq!(width(metric, "中文\u{3400}\u{20000}"), 8);
+    // Kana and CJK punctuation.
+    assert_eq!(width(metric, "ひらがなカタカナ。"), 18);
+    // Fullwidth forms.
+    assert_eq!(width(metric, "ｆｕｌｌ￥"), 10);
+    // Emoji.
+    assert_eq!(width(metric, "🌍🦀"), 4);
+}
+
+#[test]
+fn test_line_widths() {
+    let output = "fn f() {\n    \"中文\";\n}\n";
+    assert_eq!(
+        prettyplease::line_widths(output, WidthMetric::Bytes),
+        [8, 13, 1]
+    );
+    assert_eq!(
+        prettyplease::line_widths(output, WidthMetric::Chars),
+        [8, 9, 1]
+    );
+    assert_eq!(
+        prettyplease::line_widths(output, WidthMetric::UnicodeWidth),
+        [8, 11, 1]
+    );
+    assert_eq!(
+        prettyplease::max_line_width(output, WidthMetric::UnicodeWidth),
+        11
+    );
+    assert_eq!(prettyplease::max_line_width("", WidthMetric::Bytes), 0);
+}
+
+#[test]
+fn test_output_fits_margin() {
+    // Every line fits by the metric the output was formatted with, though not
+    // by a stricter one.
+    let src = format!(
+        "fn main() {{ f({}, \"{}\"); }}",
+        "x".repeat(40),
+        "中文".repeat(8)
+    );
+    let file = syn::parse_file(&src).unwrap();
+    let config = Config::default().width_metric(WidthMetric::UnicodeWidth);
+    let formatted = prettyplease::unparse_with_config(&file, &config);
+    assert_eq!(formatted.lines().count(), 3);
+    assert!(prettyplease::max_line_width(&formatted, WidthMetric::UnicodeWidth) <= 89);
+    assert!(prettyplease::max_line_width(&formatted, WidthMetric::Bytes) > 89);
+}